@@ -6,6 +6,11 @@ pub enum ResError {
     Io(std::io::Error),
     Compiler(CompilerError),
     EnvVarNotFound(EnvError),
+    #[cfg(feature = "png")]
+    Png(png::DecodingError),
+    InvalidIdentifier(String),
+    Duplicate(String),
+    Parse(String),
     Custom(&'static str),
 }
 
@@ -15,6 +20,13 @@ impl std::fmt::Display for ResError {
             Self::Io(error) => error.fmt(f),
             Self::Compiler(error) => error.fmt(f),
             Self::EnvVarNotFound(error) => error.fmt(f),
+            #[cfg(feature = "png")]
+            Self::Png(error) => write!(f, "Failed to decode PNG image! {error}"),
+            Self::InvalidIdentifier(name) => {
+                write!(f, "\"{name}\" is not a valid resource identifier!")
+            }
+            Self::Duplicate(resource) => write!(f, "{resource} is defined more than once!"),
+            Self::Parse(message) => write!(f, "Failed to parse: {message}"),
             Self::Custom(message) => write!(f, "{message}"),
         }
     }
@@ -43,3 +55,10 @@ impl From<EnvError> for ResError {
         Self::EnvVarNotFound(error)
     }
 }
+
+#[cfg(feature = "png")]
+impl From<png::DecodingError> for ResError {
+    fn from(error: png::DecodingError) -> Self {
+        Self::Png(error)
+    }
+}
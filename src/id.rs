@@ -0,0 +1,91 @@
+use crate::{ResError, util};
+
+/// Identifier of a resource.
+///
+/// It can be constructed from:
+/// * A 16-bit unsigned integer. (ex: `101`)
+/// * A name as a [`String`] or `&str`. (ex: `"LOGO"`)
+///
+/// Strings containing only a number which fits into 16 bits are treated as an integer.
+///
+/// Names must start with a letter or underscore followed by letters, digits or underscores
+/// and must not be a resource type keyword. They are case-insensitive.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ResId {
+    Int(u16),
+    Name(String),
+}
+
+impl ResId {
+    /// Checks if the identifier is usable in the resource file.
+    pub(crate) fn validate(&self) -> Result<(), ResError> {
+        match self {
+            Self::Int(_) => Ok(()),
+            Self::Name(name) => util::validate_name(name),
+        }
+    }
+
+    /// Normalized form of the identifier used for comparison.
+    pub(crate) fn normalized(&self) -> Self {
+        match self {
+            Self::Int(value) => Self::Int(*value),
+            Self::Name(name) => Self::Name(name.to_ascii_uppercase()),
+        }
+    }
+}
+
+impl std::fmt::Display for ResId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl From<u16> for ResId {
+    fn from(value: u16) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<&str> for ResId {
+    fn from(value: &str) -> Self {
+        Self::from(value.to_string())
+    }
+}
+
+impl From<String> for ResId {
+    fn from(value: String) -> Self {
+        let numeric = !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit());
+        match value.parse() {
+            Ok(number) if numeric => Self::Int(number),
+            _ => Self::Name(value),
+        }
+    }
+}
+
+impl From<&String> for ResId {
+    fn from(value: &String) -> Self {
+        Self::from(value.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion() {
+        assert_eq!(ResId::from(7), ResId::Int(7));
+        assert_eq!(ResId::from("007"), ResId::Int(7));
+        assert_eq!(ResId::from("Logo").normalized(), ResId::from("LOGO"));
+
+        assert!(ResId::from("_Logo2").validate().is_ok());
+        assert!(ResId::from("70000").validate().is_err());
+        assert!(ResId::from("+1").validate().is_err());
+        assert!(ResId::from("my logo").validate().is_err());
+        assert!(ResId::from("\"logo\"").validate().is_err());
+        assert!(ResId::from("icon").validate().is_err());
+    }
+}
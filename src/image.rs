@@ -0,0 +1,311 @@
+use crate::ResError;
+
+/// Decoded 8-bit RGBA image with rows stored top-down.
+#[derive(Debug, Clone)]
+pub(crate) struct Image {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    /// Decodes a PNG image into RGBA.
+    #[cfg(feature = "png")]
+    pub fn from_png(data: &[u8]) -> Result<Self, ResError> {
+        use png::{ColorType, Transformations};
+
+        let mut decoder = png::Decoder::new(std::io::Cursor::new(data));
+        decoder.set_transformations(Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info()?;
+        let size = reader
+            .output_buffer_size()
+            .ok_or(ResError::Custom("PNG image is too large!"))?;
+        let mut buffer = vec![0; size];
+        let info = reader.next_frame(&mut buffer)?;
+        buffer.truncate(info.buffer_size());
+
+        let pixels = match info.color_type {
+            ColorType::Rgba => buffer,
+            ColorType::Rgb => buffer
+                .chunks_exact(3)
+                .flat_map(|p| [p[0], p[1], p[2], 0xFF])
+                .collect(),
+            ColorType::GrayscaleAlpha => buffer
+                .chunks_exact(2)
+                .flat_map(|p| [p[0], p[0], p[0], p[1]])
+                .collect(),
+            ColorType::Grayscale => buffer.iter().flat_map(|&p| [p, p, p, 0xFF]).collect(),
+            ColorType::Indexed => return Err(ResError::Custom("Failed to expand PNG palette!")),
+        };
+
+        Ok(Self {
+            width: info.width,
+            height: info.height,
+            pixels,
+        })
+    }
+
+    /// Decodes an uncompressed 24-bit or 32-bit `.bmp` file into RGBA.
+    pub fn from_bmp(data: &[u8]) -> Result<Self, ResError> {
+        const BI_RGB: u32 = 0;
+        const BI_BITFIELDS: u32 = 3;
+        const UNSUPPORTED: ResError = ResError::Custom("Unsupported bitmap format!");
+        const TRUNCATED: ResError = ResError::Custom("Bitmap is truncated!");
+
+        let u16_at = |offset: usize| {
+            data.get(offset..offset + 2)
+                .map(|b| u16::from_le_bytes([b[0], b[1]]))
+        };
+        let u32_at = |offset: usize| {
+            data.get(offset..offset + 4)
+                .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+
+        if !data.starts_with(b"BM") {
+            return Err(ResError::Custom("Not a bitmap file!"));
+        }
+        let offset = u32_at(10).ok_or(TRUNCATED)? as usize;
+        let header_size = u32_at(14).ok_or(TRUNCATED)?;
+        if header_size < 40 {
+            return Err(UNSUPPORTED);
+        }
+        let width = u32_at(18).unwrap_or_default() as i32;
+        let height = u32_at(22).unwrap_or_default() as i32;
+        let bit_count = u16_at(28).unwrap_or_default();
+        let compression = u32_at(30).unwrap_or_default();
+
+        // Channel masks of red, green, blue and alpha
+        let masks = match (bit_count, compression) {
+            (24, BI_RGB) => [0x00FF0000, 0x0000FF00, 0x000000FF, 0],
+            (32, BI_RGB) => [0x00FF0000, 0x0000FF00, 0x000000FF, 0xFF000000],
+            (32, BI_BITFIELDS) => {
+                let alpha = if header_size >= 56 {
+                    u32_at(66)
+                } else {
+                    Some(0)
+                };
+                [u32_at(54), u32_at(58), u32_at(62), alpha].map(|mask| mask.unwrap_or_default())
+            }
+            _ => return Err(UNSUPPORTED),
+        };
+
+        if width <= 0 || height == 0 {
+            return Err(ResError::Custom("Bitmap has no pixels!"));
+        }
+        let (width, top_down) = (width as u32, height < 0);
+        let height = height.unsigned_abs();
+        let bytes = bit_count as usize / 8;
+        let stride = (width as usize * bytes).next_multiple_of(4);
+        let rows = data
+            .get(offset..)
+            .and_then(|rows| rows.get(..stride * height as usize))
+            .ok_or(TRUNCATED)?;
+
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for y in 0..height as usize {
+            let row = if top_down { y } else { height as usize - 1 - y };
+            let row = &rows[row * stride..][..width as usize * bytes];
+            for p in row.chunks_exact(bytes) {
+                let value = match bytes {
+                    4 => u32::from_le_bytes([p[0], p[1], p[2], p[3]]),
+                    _ => u32::from_le_bytes([p[0], p[1], p[2], 0]),
+                };
+                pixels.extend(masks.map(|mask| channel(value, mask)));
+            }
+        }
+
+        // Bitmaps without alpha are opaque
+        if masks[3] == 0 || pixels.chunks_exact(4).all(|p| p[3] == 0) {
+            pixels.chunks_exact_mut(4).for_each(|p| p[3] = 0xFF);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Encodes the image as a PNG.
+    pub fn to_png(&self) -> Result<Vec<u8>, ResError> {
+        let mut data = Vec::new();
+        let mut encoder = png::Encoder::new(&mut data, self.width, self.height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.set_compression(png::Compression::High);
+
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .map_err(|_| ResError::Custom("Failed to encode PNG image!"))?;
+        Ok(data)
+    }
+
+    /// Resamples the image to the size with a Lanczos filter.
+    ///
+    /// Colors are weighted by their alpha so transparent pixels don't bleed into the edges.
+    pub fn resize(&self, width: u32, height: u32) -> Self {
+        let premultiplied: Vec<f32> = self
+            .pixels
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let alpha = f32::from(p[3]) / 255.0;
+                [p[0], p[1], p[2]]
+                    .map(|c| f32::from(c) * alpha)
+                    .into_iter()
+                    .chain([f32::from(p[3])])
+            })
+            .collect();
+
+        // Horizontal pass, then vertical pass
+        let horizontal = resample(&premultiplied, self.width, self.height, width, true);
+        let resized = resample(&horizontal, width, self.height, height, false);
+
+        let pixels = resized
+            .chunks_exact(4)
+            .flat_map(|p| {
+                let alpha = p[3].clamp(0.0, 255.0);
+                let scale = if alpha > 0.0 { 255.0 / alpha } else { 0.0 };
+                [p[0], p[1], p[2]]
+                    .map(|c| (c * scale).round().clamp(0.0, 255.0) as u8)
+                    .into_iter()
+                    .chain([alpha.round() as u8])
+            })
+            .collect();
+
+        Self {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Pixels in BGRA order with rows stored bottom-up, as expected by a DIB.
+    pub fn to_bgra_bottom_up(&self) -> Vec<u8> {
+        let stride = self.width as usize * 4;
+        let mut output = Vec::with_capacity(self.pixels.len());
+
+        for row in self.pixels.chunks_exact(stride).rev() {
+            for p in row.chunks_exact(4) {
+                output.extend([p[2], p[1], p[0], p[3]]);
+            }
+        }
+
+        output
+    }
+}
+
+/// Extracts the channel selected by the mask, scaled to 8 bits.
+fn channel(value: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+    let bits = u64::from((value & mask) >> mask.trailing_zeros());
+    let range = u64::from(mask >> mask.trailing_zeros());
+    ((bits * 0xFF + range / 2) / range) as u8
+}
+
+/// Lanczos kernel with 3 lobes.
+fn lanczos3(x: f32) -> f32 {
+    const LOBES: f32 = 3.0;
+    if x == 0.0 {
+        1.0
+    } else if x.abs() < LOBES {
+        let x = x * std::f32::consts::PI;
+        LOBES * x.sin() * (x / LOBES).sin() / (x * x)
+    } else {
+        0.0
+    }
+}
+
+/// Resamples 4 channel pixels along one axis.
+///
+/// * `horizontal`: Resamples the rows to `target` width, otherwise the columns to `target` height.
+fn resample(pixels: &[f32], width: u32, height: u32, target: u32, horizontal: bool) -> Vec<f32> {
+    let (source, lines) = if horizontal {
+        (width, height)
+    } else {
+        (height, width)
+    };
+    let scale = source as f32 / target as f32;
+    // Widen the kernel when downscaling to average all the covered pixels
+    let filter_scale = scale.max(1.0);
+    let support = 3.0 * filter_scale;
+
+    // Contributing source pixels and their normalized weights for each target pixel
+    let weights: Vec<(usize, Vec<f32>)> = (0..target)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * scale;
+            let start = (center - support).floor().max(0.0) as usize;
+            let end = ((center + support).ceil() as usize).min(source as usize);
+            let mut weights: Vec<f32> = (start..end)
+                .map(|j| lanczos3((j as f32 + 0.5 - center) / filter_scale))
+                .collect();
+            let sum: f32 = weights.iter().sum();
+            if sum != 0.0 {
+                weights.iter_mut().for_each(|w| *w /= sum);
+            }
+            (start, weights)
+        })
+        .collect();
+
+    let (out_width, out_height) = if horizontal {
+        (target, height)
+    } else {
+        (width, target)
+    };
+    let mut output = vec![0.0; out_width as usize * out_height as usize * 4];
+    let index = |line: usize, position: usize, line_width: u32| {
+        if horizontal {
+            (line * line_width as usize + position) * 4
+        } else {
+            (position * line_width as usize + line) * 4
+        }
+    };
+
+    for line in 0..lines as usize {
+        for (i, (start, weights)) in weights.iter().enumerate() {
+            let target = index(line, i, out_width);
+            for (j, weight) in weights.iter().enumerate() {
+                let source = index(line, start + j, width);
+                for c in 0..4 {
+                    output[target + c] += pixels[source + c] * weight;
+                }
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resize() {
+        // Left half opaque red, right half transparent green
+        let pixels = (0..64 * 64)
+            .flat_map(|i| {
+                if i % 64 < 32 {
+                    [255, 0, 0, 255]
+                } else {
+                    [0, 255, 0, 0]
+                }
+            })
+            .collect();
+        let image = Image {
+            width: 64,
+            height: 64,
+            pixels,
+        };
+
+        let resized = image.resize(16, 16);
+        assert_eq!(resized.pixels.len(), 16 * 16 * 4);
+        assert_eq!(&resized.pixels[..4], &[255, 0, 0, 255]);
+        assert_eq!(resized.pixels[15 * 4 + 3], 0);
+        // The transparent color doesn't bleed into the edge
+        assert_eq!(resized.pixels[8 * 4 + 1], 0);
+    }
+}
@@ -25,16 +25,26 @@
 
 mod compiler;
 mod error;
+mod id;
+#[cfg(feature = "png")]
+mod image;
 mod res;
+mod source;
+mod symbols;
 mod util;
 mod writer;
 
 pub use error::ResError;
+pub use id::ResId;
 pub use res::*;
+pub use source::DataSource;
 
 use std::path::Path;
 use writer::ResWriter;
 
+/// File name of the C header with the resource ids.
+const HEADER: &str = "resource.h";
+
 pub trait Resource {
     fn write(&self, writer: &mut ResWriter) -> Result<(), ResError>;
 }
@@ -43,6 +53,8 @@ pub trait Resource {
 #[derive(Default)]
 pub struct ResBuilder {
     resources: Vec<Box<dyn Resource>>,
+    language: Option<u16>,
+    header: bool,
 }
 
 impl ResBuilder {
@@ -57,6 +69,24 @@ impl ResBuilder {
         Ok(Self::new().push(version_info::VersionInfo::from_env()?))
     }
 
+    /// Sets the default Windows Language Code of the resources.
+    ///
+    /// Used by every resource which doesn't specify its own language. (default: `0x0409`)
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Writes the resource ids as `#define`s to a `resource.h` next to the resource file
+    /// and includes it from there.
+    ///
+    /// C and C++ code compiled by the build script can use the same ids by adding `OUT_DIR`
+    /// to the include directories. (ex: `cc::Build::new().include(out_dir)`)
+    pub fn with_header(mut self) -> Self {
+        self.header = true;
+        self
+    }
+
     /// Adds a new resource.
     pub fn push<T: 'static + Resource>(mut self, resource: T) -> Self {
         self.resources.push(Box::new(resource));
@@ -65,22 +95,74 @@ impl ResBuilder {
 
     /// Saves the resource as file.
     pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ResError> {
+        let writer = self.write()?;
+        self.save_writer(path.as_ref(), &writer)
+    }
+
+    /// Saves a Rust module of the resource ids as file.
+    ///
+    /// It contains a constant for each resource, named after the resource's name (ex: `APP_ICON`)
+    /// or its type and number (ex: `ICON_32512`), and an enum for the command ids of each menu
+    /// and dialog box. See [`compile`](Self::compile) for the usage.
+    pub fn save_ids<P: AsRef<Path>>(&self, path: P) -> Result<(), ResError> {
+        let writer = self.write()?;
+        util::to_file(path, writer.symbols().to_rust().as_bytes())?;
+        Ok(())
+    }
+
+    fn write(&self) -> Result<ResWriter, ResError> {
         let mut writer = ResWriter::new();
+        if let Some(language) = self.language {
+            writer.set_default_language(language);
+        }
+
+        if self.header {
+            writer.line(format!("#include \"{HEADER}\""));
+        }
 
         for res in &self.resources {
             writer.new_line(); // Put one empty line between definitions
             res.write(&mut writer)?;
         }
+        writer.check_icon_indexes();
 
+        Ok(writer)
+    }
+
+    fn save_writer(&self, path: &Path, writer: &ResWriter) -> Result<(), ResError> {
         util::to_file(path, writer.as_bytes())?;
+        if self.header {
+            let header = writer.symbols().to_c_header();
+            util::to_file(path.with_file_name(HEADER), header.as_bytes())?;
+        }
         Ok(())
     }
 
     /// Compiles and links the resource to the binary being built.
+    ///
+    /// Also saves a Rust module of the resource ids as `resource_ids.rs` in `OUT_DIR`,
+    /// which can be included in the application:
+    /// ```ignore
+    /// mod ids {
+    ///     include!(concat!(env!("OUT_DIR"), "/resource_ids.rs"));
+    /// }
+    ///
+    /// let icon = unsafe { LoadIconW(instance, ids::APP_ICON) };
+    /// match ids::MainMenuCommand::try_from(command) {
+    ///     Ok(ids::MainMenuCommand::Exit) => (),
+    ///     _ => (),
+    /// }
+    /// ```
     pub fn compile(&self) -> Result<(), ResError> {
-        // Write the resource's .rc file to disk
+        let writer = self.write()?;
+
+        // Write the resource's .rc file and the ids to disk
         let rc_path = util::out_file("resource.rc")?;
-        self.save(&rc_path)?;
+        self.save_writer(&rc_path, &writer)?;
+        util::to_file(
+            util::out_file("resource_ids.rs")?,
+            writer.symbols().to_rust().as_bytes(),
+        )?;
 
         // Compile the .rc file into a .res file
         let res_path = util::out_file("resource.res")?;
@@ -0,0 +1,337 @@
+//! Accelerator table resource. ([`ACCELERATORS`](crate::accelerators::Accelerators))
+
+use crate::custom::ResType;
+use crate::{ResError, ResId, ResWriter, Resource};
+
+/// Accelerator table resource. (`ACCELERATORS`)
+///
+/// Maps keyboard shortcuts to command identifiers. Can be loaded at runtime with
+/// `LoadAcceleratorsW` and used with `TranslateAcceleratorW`.
+///
+/// ### Example:
+/// ```
+/// # use winscribe::accelerators::{Accelerator, Accelerators, VirtualKey};
+/// const ID_OPEN: u16 = 101;
+/// const ID_REFRESH: u16 = 102;
+/// const ID_HELP: u16 = 103;
+///
+/// Accelerators::new("MAIN_ACCEL")
+///     .with(Accelerator::new(VirtualKey::O, ID_OPEN).ctrl())
+///     .with(Accelerator::new(VirtualKey::F5, ID_REFRESH))
+///     .with(Accelerator::ascii('?', ID_HELP));
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/accelerators-resource>
+#[derive(Debug, Clone)]
+pub struct Accelerators {
+    id: ResId,
+    language: Option<u16>,
+    /// Entries of the table.
+    pub entries: Vec<Accelerator>,
+}
+
+impl Accelerators {
+    /// Creates a new, empty accelerator table.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    pub fn new<N: Into<ResId>>(id: N) -> Self {
+        Self {
+            id: id.into(),
+            language: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds an entry to the table.
+    pub fn with(mut self, accelerator: Accelerator) -> Self {
+        self.entries.push(accelerator);
+        self
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+}
+
+impl Resource for Accelerators {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        writer.define(ResType::ACCELERATOR, self.id.clone(), self.language)?;
+        writer.line(format!("{} ACCELERATORS", self.id));
+        writer.begin();
+        for entry in &self.entries {
+            writer.line(entry.to_line()?);
+        }
+        writer.end();
+        Ok(())
+    }
+}
+
+/// Key of an accelerator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// Virtual-key code. (`VIRTKEY`)
+    Virtual(VirtualKey),
+    /// Character code. (`ASCII`) Case-sensitive.
+    Ascii(char),
+}
+
+/// Keyboard shortcut mapped to a command identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Accelerator {
+    /// Key which triggers the command.
+    pub key: Key,
+    /// Command identifier sent with the `WM_COMMAND` message.
+    pub command: u16,
+    /// `CONTROL` modifier. Only valid with virtual keys.
+    pub ctrl: bool,
+    /// `ALT` modifier.
+    pub alt: bool,
+    /// `SHIFT` modifier. Only valid with virtual keys.
+    pub shift: bool,
+    /// Do not highlight the top-level menu item when used. (`NOINVERT`)
+    pub no_invert: bool,
+}
+
+impl Accelerator {
+    /// Creates an accelerator from a virtual key.
+    pub fn new(key: VirtualKey, command: u16) -> Self {
+        Self::from_key(Key::Virtual(key), command)
+    }
+
+    /// Creates an accelerator from a character.
+    pub fn ascii(key: char, command: u16) -> Self {
+        Self::from_key(Key::Ascii(key), command)
+    }
+
+    fn from_key(key: Key, command: u16) -> Self {
+        Self {
+            key,
+            command,
+            ctrl: false,
+            alt: false,
+            shift: false,
+            no_invert: false,
+        }
+    }
+
+    /// Requires the Ctrl key to be held down.
+    pub fn ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    /// Requires the Alt key to be held down.
+    pub fn alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    /// Requires the Shift key to be held down.
+    pub fn shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    /// Does not highlight the top-level menu item when used.
+    pub fn no_invert(mut self) -> Self {
+        self.no_invert = true;
+        self
+    }
+
+    fn to_line(self) -> Result<String, ResError> {
+        let mut line = match self.key {
+            Key::Virtual(key) => format!("{:#04X}, {}, VIRTKEY", key as u8, self.command),
+            Key::Ascii(key) => {
+                if self.ctrl || self.shift {
+                    return Err(ResError::Custom(
+                        "Ctrl and Shift modifiers are only supported with virtual keys!",
+                    ));
+                }
+                let code = u16::try_from(u32::from(key))
+                    .map_err(|_| ResError::Custom("Accelerator character is out of range!"))?;
+                format!("{code}, {}, ASCII", self.command)
+            }
+        };
+
+        for (enabled, option) in [
+            (self.no_invert, "NOINVERT"),
+            (self.ctrl, "CONTROL"),
+            (self.alt, "ALT"),
+            (self.shift, "SHIFT"),
+        ] {
+            if enabled {
+                line.push_str(", ");
+                line.push_str(option);
+            }
+        }
+
+        Ok(line)
+    }
+}
+
+/// Virtual-key codes. (`VK_*`)
+///
+/// More info: <https://learn.microsoft.com/windows/win32/inputdev/virtual-key-codes>
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum VirtualKey {
+    Back = 0x08,
+    Tab = 0x09,
+    Clear = 0x0C,
+    Return = 0x0D,
+    Pause = 0x13,
+    Escape = 0x1B,
+    Space = 0x20,
+    PageUp = 0x21,
+    PageDown = 0x22,
+    End = 0x23,
+    Home = 0x24,
+    Left = 0x25,
+    Up = 0x26,
+    Right = 0x27,
+    Down = 0x28,
+    Print = 0x2A,
+    Snapshot = 0x2C,
+    Insert = 0x2D,
+    Delete = 0x2E,
+    Help = 0x2F,
+    Key0 = 0x30,
+    Key1 = 0x31,
+    Key2 = 0x32,
+    Key3 = 0x33,
+    Key4 = 0x34,
+    Key5 = 0x35,
+    Key6 = 0x36,
+    Key7 = 0x37,
+    Key8 = 0x38,
+    Key9 = 0x39,
+    A = 0x41,
+    B = 0x42,
+    C = 0x43,
+    D = 0x44,
+    E = 0x45,
+    F = 0x46,
+    G = 0x47,
+    H = 0x48,
+    I = 0x49,
+    J = 0x4A,
+    K = 0x4B,
+    L = 0x4C,
+    M = 0x4D,
+    N = 0x4E,
+    O = 0x4F,
+    P = 0x50,
+    Q = 0x51,
+    R = 0x52,
+    S = 0x53,
+    T = 0x54,
+    U = 0x55,
+    V = 0x56,
+    W = 0x57,
+    X = 0x58,
+    Y = 0x59,
+    Z = 0x5A,
+    Apps = 0x5D,
+    Numpad0 = 0x60,
+    Numpad1 = 0x61,
+    Numpad2 = 0x62,
+    Numpad3 = 0x63,
+    Numpad4 = 0x64,
+    Numpad5 = 0x65,
+    Numpad6 = 0x66,
+    Numpad7 = 0x67,
+    Numpad8 = 0x68,
+    Numpad9 = 0x69,
+    Multiply = 0x6A,
+    Add = 0x6B,
+    Separator = 0x6C,
+    Subtract = 0x6D,
+    Decimal = 0x6E,
+    Divide = 0x6F,
+    F1 = 0x70,
+    F2 = 0x71,
+    F3 = 0x72,
+    F4 = 0x73,
+    F5 = 0x74,
+    F6 = 0x75,
+    F7 = 0x76,
+    F8 = 0x77,
+    F9 = 0x78,
+    F10 = 0x79,
+    F11 = 0x7A,
+    F12 = 0x7B,
+    F13 = 0x7C,
+    F14 = 0x7D,
+    F15 = 0x7E,
+    F16 = 0x7F,
+    F17 = 0x80,
+    F18 = 0x81,
+    F19 = 0x82,
+    F20 = 0x83,
+    F21 = 0x84,
+    F22 = 0x85,
+    F23 = 0x86,
+    F24 = 0x87,
+    BrowserBack = 0xA6,
+    BrowserForward = 0xA7,
+    BrowserRefresh = 0xA8,
+    BrowserSearch = 0xAA,
+    /// `;:` key on US keyboards.
+    Oem1 = 0xBA,
+    /// `+` key on any keyboard.
+    OemPlus = 0xBB,
+    /// `,` key on any keyboard.
+    OemComma = 0xBC,
+    /// `-` key on any keyboard.
+    OemMinus = 0xBD,
+    /// `.` key on any keyboard.
+    OemPeriod = 0xBE,
+    /// `/?` key on US keyboards.
+    Oem2 = 0xBF,
+    /// `` `~ `` key on US keyboards.
+    Oem3 = 0xC0,
+    /// `[{` key on US keyboards.
+    Oem4 = 0xDB,
+    /// `\|` key on US keyboards.
+    Oem5 = 0xDC,
+    /// `]}` key on US keyboards.
+    Oem6 = 0xDD,
+    /// `'"` key on US keyboards.
+    Oem7 = 0xDE,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table() {
+        let table = Accelerators::new("MAIN_ACCEL")
+            .with(Accelerator::new(VirtualKey::O, 101).ctrl())
+            .with(
+                Accelerator::new(VirtualKey::F5, 102)
+                    .shift()
+                    .alt()
+                    .no_invert(),
+            )
+            .with(Accelerator::ascii('?', 103));
+
+        let mut writer = ResWriter::new();
+        table.write(&mut writer).unwrap();
+        assert!(String::from_utf8_lossy(writer.as_bytes()).ends_with(
+            "MAIN_ACCEL ACCELERATORS\n{\n\
+             \t0x4F, 101, VIRTKEY, CONTROL\n\
+             \t0x74, 102, VIRTKEY, NOINVERT, ALT, SHIFT\n\
+             \t63, 103, ASCII\n}\n"
+        ));
+
+        // Modifiers which only work with virtual keys
+        assert!(Accelerator::ascii('a', 1).ctrl().to_line().is_err());
+        assert!(Accelerator::ascii('a', 1).shift().to_line().is_err());
+        assert!(Accelerator::ascii('a', 1).alt().to_line().is_ok());
+        // Outside of the 16-bit range
+        assert!(Accelerator::ascii('😀', 1).to_line().is_err());
+    }
+}
@@ -0,0 +1,400 @@
+//! Animated cursor and icon resources. ([`ANICURSOR`](crate::animated::AnimatedCursor), [`ANIICON`](crate::animated::AnimatedIcon))
+
+use crate::custom::ResType;
+use crate::util::{self, push_u32, read_u16, read_u32};
+use crate::{ResError, ResId, ResWriter, Resource};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Frames are icons or cursors instead of raw bitmaps. (`AF_ICON`)
+const AF_ICON: u32 = 0x1;
+/// The animation contains a `seq ` chunk. (`AF_SEQUENCE`)
+const AF_SEQUENCE: u32 = 0x2;
+/// Length of a jiffy, the time unit of the animation.
+const JIFFY: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// Animated cursor resource. (`ANICURSOR`)
+///
+/// Can be loaded at runtime with `LoadCursorW` or `LoadImageW`.
+///
+/// ### Example:
+/// ```
+/// # use winscribe::animated::{AnimatedCursor, Animation};
+/// # use std::time::Duration;
+/// // From an .ani file:
+/// AnimatedCursor::new("BUSY", "cursors/busy.ani");
+/// // From frames:
+/// AnimatedCursor::from_frames(
+///     "LOADING",
+///     Animation::new()
+///         .with_frame("cursors/loading1.cur", Duration::from_millis(100))
+///         .with_frame("cursors/loading2.cur", Duration::from_millis(250)),
+/// );
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/anicursor-resource>
+pub struct AnimatedCursor {
+    id: ResId,
+    language: Option<u16>,
+    source: AniSource,
+}
+
+impl AnimatedCursor {
+    /// Creates a new animated cursor resource.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `path`: Path to the `.ani` file.
+    pub fn new<N, P>(id: N, path: P) -> Self
+    where
+        N: Into<ResId>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            id: id.into(),
+            language: None,
+            source: AniSource::File(path.into()),
+        }
+    }
+
+    /// Creates a new animated cursor resource from a sequence of `.cur` frames.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `animation`: Frames of the animation.
+    pub fn from_frames<N: Into<ResId>>(id: N, animation: Animation) -> Self {
+        Self {
+            id: id.into(),
+            language: None,
+            source: AniSource::Frames(animation),
+        }
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+}
+
+impl Resource for AnimatedCursor {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        let path = self.source.path()?;
+        writer.define(ResType::ANICURSOR, self.id.clone(), self.language)?;
+        writer.line(format!(
+            "{} ANICURSOR \"{}\"",
+            self.id,
+            util::escape_path(path)?
+        ));
+        Ok(())
+    }
+}
+
+/// Animated icon resource. (`ANIICON`)
+///
+/// Can be loaded at runtime with `LoadImageW` and displayed with `DrawIconEx`.
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/aniicon-resource>
+pub struct AnimatedIcon {
+    id: ResId,
+    language: Option<u16>,
+    source: AniSource,
+}
+
+impl AnimatedIcon {
+    /// Creates a new animated icon resource.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `path`: Path to the `.ani` file.
+    pub fn new<N, P>(id: N, path: P) -> Self
+    where
+        N: Into<ResId>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            id: id.into(),
+            language: None,
+            source: AniSource::File(path.into()),
+        }
+    }
+
+    /// Creates a new animated icon resource from a sequence of `.ico` frames.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `animation`: Frames of the animation.
+    pub fn from_frames<N: Into<ResId>>(id: N, animation: Animation) -> Self {
+        Self {
+            id: id.into(),
+            language: None,
+            source: AniSource::Frames(animation),
+        }
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+}
+
+impl Resource for AnimatedIcon {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        let path = self.source.path()?;
+        writer.define(ResType::ANIICON, self.id.clone(), self.language)?;
+        writer.line(format!(
+            "{} ANIICON \"{}\"",
+            self.id,
+            util::escape_path(path)?
+        ));
+        Ok(())
+    }
+}
+
+enum AniSource {
+    File(PathBuf),
+    Frames(Animation),
+}
+
+impl AniSource {
+    /// Path to the validated `.ani` file.
+    fn path(&self) -> Result<PathBuf, ResError> {
+        match self {
+            Self::File(path) => {
+                validate(&std::fs::read(path)?).map_err(|e| error(path, e))?;
+                Ok(path.clone())
+            }
+            Self::Frames(animation) => util::hashed_out_file("ani", "ani", &animation.encode()?),
+        }
+    }
+}
+
+/// Frames of an animated cursor or icon with their display time.
+#[derive(Debug, Clone, Default)]
+pub struct Animation {
+    frames: Vec<(PathBuf, Duration)>,
+}
+
+impl Animation {
+    /// Creates a new, empty animation.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a frame displayed for the `duration`. It is rounded to 1/60 of a second.
+    ///
+    /// * `path`: Path to the `.ico` or `.cur` file.
+    pub fn with_frame<P: Into<PathBuf>>(mut self, path: P, duration: Duration) -> Self {
+        self.frames.push((path.into(), duration));
+        self
+    }
+
+    /// Encodes the frames as an `.ani` file.
+    fn encode(&self) -> Result<Vec<u8>, ResError> {
+        let mut frames = Vec::with_capacity(self.frames.len());
+        for (path, duration) in &self.frames {
+            let data = std::fs::read(path)?;
+            validate_frame(&data).map_err(|e| error(path, e))?;
+            frames.push((data, jiffies(*duration)));
+        }
+        encode(&frames)
+    }
+}
+
+/// Converts the duration to jiffies. (1/60 of a second, at least 1)
+fn jiffies(duration: Duration) -> u32 {
+    let jiffies = (duration.as_nanos() + JIFFY.as_nanos() / 2) / JIFFY.as_nanos();
+    u32::try_from(jiffies).unwrap_or(u32::MAX).max(1)
+}
+
+/// Builds the RIFF `ACON` file from the frames' data and display rates.
+fn encode(frames: &[(Vec<u8>, u32)]) -> Result<Vec<u8>, ResError> {
+    if frames.is_empty() {
+        return Err(ResError::Custom("Animation must have at least one frame!"));
+    }
+    let count = frames.len() as u32;
+    let rate = frames[0].1;
+    let uniform = frames.iter().all(|(_, jiffies)| *jiffies == rate);
+
+    let mut data = Vec::new();
+    data.extend(b"RIFF");
+    push_u32(&mut data, 0); // Size, set below
+    data.extend(b"ACON");
+
+    let mut header = Vec::with_capacity(36);
+    for value in [36, count, count, 0, 0, 0, 0, rate, AF_ICON] {
+        push_u32(&mut header, value);
+    }
+    push_chunk(&mut data, b"anih", &header);
+
+    if !uniform {
+        let mut rates = Vec::with_capacity(frames.len() * 4);
+        for (_, jiffies) in frames {
+            push_u32(&mut rates, *jiffies);
+        }
+        push_chunk(&mut data, b"rate", &rates);
+    }
+
+    let mut list = b"fram".to_vec();
+    for (frame, _) in frames {
+        push_chunk(&mut list, b"icon", frame);
+    }
+    push_chunk(&mut data, b"LIST", &list);
+
+    let size =
+        u32::try_from(data.len() - 8).map_err(|_| ResError::Custom("Animation is too large!"))?;
+    data[4..8].copy_from_slice(&size.to_le_bytes());
+    Ok(data)
+}
+
+/// Appends a RIFF chunk padded to an even size.
+fn push_chunk(data: &mut Vec<u8>, id: &[u8; 4], content: &[u8]) {
+    data.extend(id);
+    push_u32(data, content.len() as u32);
+    data.extend(content);
+    if !content.len().is_multiple_of(2) {
+        data.push(0);
+    }
+}
+
+/// Checks the RIFF structure of an `.ani` file.
+fn validate(data: &[u8]) -> Result<(), &'static str> {
+    if data.get(..4) != Some(b"RIFF") || data.get(8..12) != Some(b"ACON") {
+        return Err("not a RIFF ACON file");
+    }
+    let size = read_u32(data, 4).ok_or("RIFF header is truncated")? as usize;
+    let content = data
+        .get(12..size.checked_add(8).ok_or("invalid RIFF size")?)
+        .ok_or("RIFF chunk exceeds the file size")?;
+
+    let mut header = None;
+    let mut frames = Vec::new();
+    let mut rates = None;
+    let mut sequence = None;
+
+    for (id, chunk) in chunks(content)? {
+        match id {
+            b"anih" => {
+                if chunk.len() < 36 || read_u32(chunk, 0) != Some(36) {
+                    return Err("invalid \"anih\" header");
+                }
+                header = Some(chunk);
+            }
+            b"rate" => rates = Some(chunk),
+            b"seq " => sequence = Some(chunk),
+            b"LIST" if chunk.starts_with(b"fram") => {
+                for (id, frame) in chunks(&chunk[4..])? {
+                    if id == b"icon" {
+                        frames.push(frame);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let header = header.ok_or("missing \"anih\" header")?;
+    let frame_count = read_u32(header, 4).unwrap_or_default() as usize;
+    let steps = read_u32(header, 8).unwrap_or_default() as usize;
+    let flags = read_u32(header, 32).unwrap_or_default();
+
+    if frame_count == 0 || steps == 0 {
+        return Err("animation has no frames");
+    }
+    if frames.len() != frame_count {
+        return Err("number of frames does not match the header");
+    }
+    if flags & AF_ICON != 0 {
+        for frame in &frames {
+            validate_frame(frame)?;
+        }
+    }
+    if rates.is_some_and(|rates| rates.len() < steps * 4) {
+        return Err("\"rate\" chunk is shorter than the number of steps");
+    }
+
+    match sequence {
+        Some(sequence) => {
+            if sequence.len() < steps * 4 {
+                return Err("\"seq \" chunk is shorter than the number of steps");
+            }
+            let valid = (0..steps).all(|step| {
+                read_u32(sequence, step * 4).is_some_and(|frame| (frame as usize) < frame_count)
+            });
+            if !valid {
+                return Err("\"seq \" chunk references a missing frame");
+            }
+        }
+        None if flags & AF_SEQUENCE != 0 => return Err("missing \"seq \" chunk"),
+        None if steps != frame_count => {
+            return Err("number of steps does not match the number of frames");
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// Identifier and content of a RIFF chunk.
+type Chunk<'a> = (&'a [u8; 4], &'a [u8]);
+
+/// Splits the data into RIFF chunks.
+fn chunks(mut data: &[u8]) -> Result<Vec<Chunk<'_>>, &'static str> {
+    let mut chunks = Vec::new();
+    while data.len() >= 8 {
+        let id = data[..4].try_into().unwrap();
+        let size = read_u32(data, 4).unwrap() as usize;
+        let chunk = data
+            .get(8..8 + size)
+            .ok_or("RIFF chunk exceeds its parent")?;
+        chunks.push((id, chunk));
+        data = data.get(8 + size.next_multiple_of(2)..).unwrap_or_default();
+    }
+    Ok(chunks)
+}
+
+/// Checks the header of an `.ico` or `.cur` frame.
+fn validate_frame(data: &[u8]) -> Result<(), &'static str> {
+    let valid = read_u16(data, 0) == Some(0)
+        && matches!(read_u16(data, 2), Some(1 | 2))
+        && read_u16(data, 4)
+            .is_some_and(|count| count > 0 && data.len() >= 6 + usize::from(count) * 16);
+
+    if valid {
+        Ok(())
+    } else {
+        Err("frame is not a valid icon or cursor")
+    }
+}
+
+fn error(path: &Path, message: &str) -> ResError {
+    ResError::Parse(format!("animation \"{}\": {message}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Icon directory with a single 1x1 entry and 1 byte of image data.
+    fn frame() -> Vec<u8> {
+        let mut data = vec![0, 0, 1, 0, 1, 0, 1, 1, 0, 0, 1, 0, 32, 0];
+        data.extend(1u32.to_le_bytes());
+        data.extend(22u32.to_le_bytes());
+        data.push(0xFF);
+        data
+    }
+
+    #[test]
+    fn ani() {
+        let data = encode(&[(frame(), 6), (frame(), 12)]).unwrap();
+        assert_eq!(&data[..4], b"RIFF");
+        assert_eq!(read_u32(&data, 4).unwrap() as usize, data.len() - 8);
+        assert_eq!(&data[12..16], b"anih");
+        assert_eq!(&data[56..60], b"rate");
+        assert!(validate(&data).is_ok());
+
+        // Odd sized frames are padded
+        assert_eq!(data.len() % 2, 0);
+
+        assert!(validate(&data[..data.len() - 4]).is_err());
+        assert_eq!(jiffies(Duration::from_millis(100)), 6);
+        assert_eq!(jiffies(Duration::ZERO), 1);
+    }
+}
@@ -0,0 +1,152 @@
+//! Bitmap resource. ([`BITMAP`](crate::bitmap::Bitmap))
+
+use crate::custom::ResType;
+use crate::{ResError, ResId, ResWriter, Resource, util};
+use std::path::PathBuf;
+
+/// Bitmap resource. (`BITMAP`)
+///
+/// Can be loaded at runtime with `LoadImageW` or `LoadBitmapW`.
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/bitmap-resource>
+pub struct Bitmap {
+    id: ResId,
+    language: Option<u16>,
+    source: BitmapSource,
+}
+
+enum BitmapSource {
+    Bmp(PathBuf),
+    #[cfg(feature = "png")]
+    Png(PathBuf),
+}
+
+impl Bitmap {
+    /// Creates a new bitmap resource.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `path`: Path to the `.bmp` file.
+    pub fn new<N, P>(id: N, path: P) -> Self
+    where
+        N: Into<ResId>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            id: id.into(),
+            language: None,
+            source: BitmapSource::Bmp(path.into()),
+        }
+    }
+
+    /// Creates a new bitmap resource from a PNG image.
+    ///
+    /// The image is converted to a 32-bit DIB with its alpha channel preserved.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `path`: Path to the `.png` file.
+    #[cfg(feature = "png")]
+    pub fn from_png<N, P>(id: N, path: P) -> Self
+    where
+        N: Into<ResId>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            id: id.into(),
+            language: None,
+            source: BitmapSource::Png(path.into()),
+        }
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+}
+
+impl Resource for Bitmap {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        let path = match &self.source {
+            BitmapSource::Bmp(path) => path.clone(),
+            #[cfg(feature = "png")]
+            BitmapSource::Png(path) => {
+                let image = crate::image::Image::from_png(&std::fs::read(path)?)?;
+                util::hashed_out_file("bitmap", "bmp", &bmp_file(&image))?
+            }
+        };
+
+        writer.define(ResType::BITMAP, self.id.clone(), self.language)?;
+        writer.line(format!(
+            "{} BITMAP \"{}\"",
+            self.id,
+            util::escape_path(path)?
+        ));
+        Ok(())
+    }
+}
+
+/// Encodes the image as a 32-bit `.bmp` file with a `BITMAPV5HEADER`.
+#[cfg(feature = "png")]
+fn bmp_file(image: &crate::image::Image) -> Vec<u8> {
+    const FILE_HEADER_SIZE: u32 = 14;
+    const INFO_HEADER_SIZE: u32 = 124;
+    const BI_BITFIELDS: u32 = 3;
+    const LCS_SRGB: u32 = 0x73524742;
+    const LCS_GM_IMAGES: u32 = 4;
+    const PIXELS_PER_METER: i32 = 2835; // 72 DPI
+
+    let pixels = image.to_bgra_bottom_up();
+    let offset = FILE_HEADER_SIZE + INFO_HEADER_SIZE;
+    let mut data = Vec::with_capacity(offset as usize + pixels.len());
+
+    // BITMAPFILEHEADER
+    data.extend(b"BM");
+    data.extend((offset + pixels.len() as u32).to_le_bytes());
+    data.extend(0u32.to_le_bytes()); // Reserved
+    data.extend(offset.to_le_bytes());
+
+    // BITMAPV5HEADER
+    data.extend(INFO_HEADER_SIZE.to_le_bytes());
+    data.extend((image.width as i32).to_le_bytes());
+    data.extend((image.height as i32).to_le_bytes()); // Positive: bottom-up
+    data.extend(1u16.to_le_bytes()); // Planes
+    data.extend(32u16.to_le_bytes()); // Bit count
+    data.extend(BI_BITFIELDS.to_le_bytes());
+    data.extend((pixels.len() as u32).to_le_bytes());
+    data.extend(PIXELS_PER_METER.to_le_bytes());
+    data.extend(PIXELS_PER_METER.to_le_bytes());
+    data.extend(0u32.to_le_bytes()); // Colors used
+    data.extend(0u32.to_le_bytes()); // Colors important
+    data.extend(0x00FF0000u32.to_le_bytes()); // Red mask
+    data.extend(0x0000FF00u32.to_le_bytes()); // Green mask
+    data.extend(0x000000FFu32.to_le_bytes()); // Blue mask
+    data.extend(0xFF000000u32.to_le_bytes()); // Alpha mask
+    data.extend(LCS_SRGB.to_le_bytes());
+    data.extend([0; 36]); // Endpoints
+    data.extend([0; 12]); // Gamma
+    data.extend(LCS_GM_IMAGES.to_le_bytes());
+    data.extend([0; 12]); // Profile data, size & reserved
+
+    data.extend(pixels);
+    data
+}
+
+#[cfg(all(test, feature = "png"))]
+mod tests {
+    use super::*;
+    use crate::image::Image;
+
+    #[test]
+    fn bmp_layout() {
+        let image = Image {
+            width: 2,
+            height: 1,
+            pixels: vec![1, 2, 3, 4, 5, 6, 7, 8],
+        };
+        let data = bmp_file(&image);
+
+        assert_eq!(data.len(), 14 + 124 + 8);
+        assert_eq!(&data[..2], b"BM");
+        assert_eq!(&data[138..], &[3, 2, 1, 4, 7, 6, 5, 8]);
+    }
+}
@@ -0,0 +1,162 @@
+//! User-defined resource. ([`CustomResource`](crate::custom::CustomResource))
+
+use crate::{DataSource, ResError, ResId, ResWriter, Resource, util};
+
+/// User-defined resource.
+///
+/// Embeds data under a custom resource type, which can be located at runtime with
+/// `FindResourceW` using the same type name or number.
+///
+/// ### Example:
+/// ```
+/// # use winscribe::custom::CustomResource;
+/// # use std::path::Path;
+/// CustomResource::new("REGISTRY", 101, Path::new("server.rgs"));
+/// CustomResource::new(300, "LOGO", include_bytes!("../../LICENSE-MIT")).with_language(0x0409);
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/user-defined-resource>
+pub struct CustomResource {
+    /// Name or number of the resource type.
+    pub type_id: ResType,
+    /// A unique name or a 16-bit unsigned integer.
+    pub id: ResId,
+    /// Windows Language Code. Uses the builder's default when not set.
+    ///
+    /// More info: <https://learn.microsoft.com/openspecs/windows_protocols/ms-lcid>
+    pub language: Option<u16>,
+    /// Content of the resource.
+    pub data: DataSource,
+}
+
+impl CustomResource {
+    /// Creates a new user-defined resource.
+    ///
+    /// * `type_id`: Name or number of the resource type.
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `data`: Path to a file or the in-memory bytes.
+    pub fn new<T, N, D>(type_id: T, id: N, data: D) -> Self
+    where
+        T: Into<ResType>,
+        N: Into<ResId>,
+        D: Into<DataSource>,
+    {
+        Self {
+            type_id: type_id.into(),
+            id: id.into(),
+            language: None,
+            data: data.into(),
+        }
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+}
+
+impl Resource for CustomResource {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        if let ResType::Name(name) = &self.type_id {
+            util::validate_name(name)?;
+        }
+
+        let path = self.data.path("custom", "bin")?;
+
+        writer.define(self.type_id.clone(), self.id.clone(), self.language)?;
+        writer.line(format!(
+            "{} {} \"{}\"",
+            self.id,
+            self.type_id,
+            util::escape_path(path)?
+        ));
+        Ok(())
+    }
+}
+
+/// Type of a user-defined resource.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResType {
+    /// Type number. Values below 256 are reserved for the predefined types.
+    Int(u16),
+    /// Type name. (ex: `PNG`, `REGISTRY`, `TYPELIB`)
+    Name(String),
+}
+
+/// Predefined resource types.
+impl ResType {
+    /// `RT_CURSOR`
+    pub const CURSOR: Self = Self::Int(1);
+    /// `RT_BITMAP`
+    pub const BITMAP: Self = Self::Int(2);
+    /// `RT_ICON`
+    pub const ICON: Self = Self::Int(3);
+    /// `RT_MENU`
+    pub const MENU: Self = Self::Int(4);
+    /// `RT_DIALOG`
+    pub const DIALOG: Self = Self::Int(5);
+    /// `RT_STRING`
+    pub const STRING: Self = Self::Int(6);
+    /// `RT_FONTDIR`
+    pub const FONTDIR: Self = Self::Int(7);
+    /// `RT_FONT`
+    pub const FONT: Self = Self::Int(8);
+    /// `RT_ACCELERATOR`
+    pub const ACCELERATOR: Self = Self::Int(9);
+    /// `RT_RCDATA`
+    pub const RCDATA: Self = Self::Int(10);
+    /// `RT_MESSAGETABLE`
+    pub const MESSAGETABLE: Self = Self::Int(11);
+    /// `RT_GROUP_CURSOR`
+    pub const GROUP_CURSOR: Self = Self::Int(12);
+    /// `RT_GROUP_ICON`
+    pub const GROUP_ICON: Self = Self::Int(14);
+    /// `RT_VERSION`
+    pub const VERSION: Self = Self::Int(16);
+    /// `RT_ANICURSOR`
+    pub const ANICURSOR: Self = Self::Int(21);
+    /// `RT_ANIICON`
+    pub const ANIICON: Self = Self::Int(22);
+    /// `RT_HTML`
+    pub const HTML: Self = Self::Int(23);
+    /// `RT_MANIFEST`
+    pub const MANIFEST: Self = Self::Int(24);
+}
+
+impl ResType {
+    /// Normalized form of the type used for comparison.
+    pub(crate) fn normalized(&self) -> Self {
+        match self {
+            Self::Int(value) => Self::Int(*value),
+            Self::Name(name) => Self::Name(name.to_ascii_uppercase()),
+        }
+    }
+}
+
+impl std::fmt::Display for ResType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(value) => write!(f, "{value}"),
+            Self::Name(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+impl From<u16> for ResType {
+    fn from(value: u16) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<&str> for ResType {
+    fn from(value: &str) -> Self {
+        Self::Name(value.to_string())
+    }
+}
+
+impl From<String> for ResType {
+    fn from(value: String) -> Self {
+        Self::Name(value)
+    }
+}
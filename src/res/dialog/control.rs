@@ -0,0 +1,207 @@
+use super::{ExStyle, Rect, Style};
+use crate::ResWriter;
+use crate::util::{self, push_u16, push_u32, push_utf16};
+
+/// A control inside a [`Dialog`](super::Dialog).
+#[derive(Debug, Clone)]
+pub struct Control {
+    /// Control identifier. (ex: `IDOK`)
+    pub id: u16,
+    /// Text of the control.
+    pub text: String,
+    /// Window class of the control.
+    pub class: ControlClass,
+    /// Position and size of the control in dialog units.
+    pub rect: Rect,
+    /// Style of the control.
+    pub style: Style,
+    /// Extended style of the control.
+    pub ex_style: ExStyle,
+    /// Context help identifier.
+    pub help_id: u32,
+}
+
+impl Control {
+    /// Creates a new control of the specified class.
+    ///
+    /// The control is visible but has no other styles set.
+    pub fn new<T, C>(id: u16, text: T, class: C, rect: Rect) -> Self
+    where
+        T: Into<String>,
+        C: Into<ControlClass>,
+    {
+        Self {
+            id,
+            text: text.into(),
+            class: class.into(),
+            rect,
+            style: Style::WS_CHILD | Style::WS_VISIBLE,
+            ex_style: ExStyle::default(),
+            help_id: 0,
+        }
+    }
+
+    /// Push button. (`BS_PUSHBUTTON`)
+    pub fn button<T: Into<String>>(id: u16, text: T, rect: Rect) -> Self {
+        Self::new(id, text, ControlClass::Button, rect)
+            .with_style(Style::BS_PUSHBUTTON | Style::WS_TABSTOP)
+    }
+
+    /// Default push button. (`BS_DEFPUSHBUTTON`)
+    pub fn default_button<T: Into<String>>(id: u16, text: T, rect: Rect) -> Self {
+        Self::new(id, text, ControlClass::Button, rect)
+            .with_style(Style::BS_DEFPUSHBUTTON | Style::WS_TABSTOP)
+    }
+
+    /// Check box. (`BS_AUTOCHECKBOX`)
+    pub fn checkbox<T: Into<String>>(id: u16, text: T, rect: Rect) -> Self {
+        Self::new(id, text, ControlClass::Button, rect)
+            .with_style(Style::BS_AUTOCHECKBOX | Style::WS_TABSTOP)
+    }
+
+    /// Radio button. (`BS_AUTORADIOBUTTON`)
+    pub fn radio_button<T: Into<String>>(id: u16, text: T, rect: Rect) -> Self {
+        Self::new(id, text, ControlClass::Button, rect).with_style(Style::BS_AUTORADIOBUTTON)
+    }
+
+    /// Group box. (`BS_GROUPBOX`)
+    pub fn group_box<T: Into<String>>(id: u16, text: T, rect: Rect) -> Self {
+        Self::new(id, text, ControlClass::Button, rect).with_style(Style::BS_GROUPBOX)
+    }
+
+    /// Single-line edit control. (`EDITTEXT`)
+    pub fn edit(id: u16, rect: Rect) -> Self {
+        Self::new(id, "", ControlClass::Edit, rect)
+            .with_style(Style::ES_AUTOHSCROLL | Style::WS_BORDER | Style::WS_TABSTOP)
+    }
+
+    /// Left-aligned static text. (`LTEXT`)
+    pub fn label<T: Into<String>>(id: u16, text: T, rect: Rect) -> Self {
+        Self::new(id, text, ControlClass::Static, rect).with_style(Style::SS_LEFT | Style::WS_GROUP)
+    }
+
+    /// Drop-down list combo box. (`CBS_DROPDOWNLIST`)
+    ///
+    /// The height of the `rect` includes the opened list.
+    pub fn combo_box(id: u16, rect: Rect) -> Self {
+        Self::new(id, "", ControlClass::ComboBox, rect)
+            .with_style(Style::CBS_DROPDOWNLIST | Style::WS_VSCROLL | Style::WS_TABSTOP)
+    }
+
+    /// List box. (`LISTBOX`)
+    pub fn list_box(id: u16, rect: Rect) -> Self {
+        Self::new(id, "", ControlClass::ListBox, rect).with_style(
+            Style::LBS_NOTIFY | Style::WS_BORDER | Style::WS_VSCROLL | Style::WS_TABSTOP,
+        )
+    }
+
+    /// List-view control in report mode. (`SysListView32`)
+    pub fn list_view(id: u16, rect: Rect) -> Self {
+        Self::new(id, "", "SysListView32", rect)
+            .with_style(Style::LVS_REPORT | Style::WS_BORDER | Style::WS_TABSTOP)
+    }
+
+    /// Adds style flags to the control.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style |= style;
+        self
+    }
+
+    /// Adds extended style flags to the control.
+    pub fn with_ex_style(mut self, ex_style: ExStyle) -> Self {
+        self.ex_style |= ex_style;
+        self
+    }
+
+    pub(super) fn write(&self, writer: &mut ResWriter) {
+        writer.line(format!(
+            "CONTROL \"{}\", {}, \"{}\", {:#010X}, {}, {}, {}, {}, {:#X}, {}",
+            util::escape(&self.text),
+            self.id,
+            self.class.name(),
+            self.style.0,
+            self.rect.x,
+            self.rect.y,
+            self.rect.width,
+            self.rect.height,
+            self.ex_style.0,
+            self.help_id,
+        ));
+    }
+
+    /// Appends the `DLGITEMTEMPLATEEX` of the control.
+    pub(super) fn encode(&self, data: &mut Vec<u8>) {
+        // Each item starts on a DWORD boundary
+        data.resize(data.len().next_multiple_of(4), 0);
+
+        push_u32(data, self.help_id);
+        push_u32(data, self.ex_style.0);
+        push_u32(data, self.style.0);
+        self.rect.encode(data);
+        push_u32(data, self.id.into());
+
+        match self.class.atom() {
+            Some(atom) => {
+                push_u16(data, 0xFFFF);
+                push_u16(data, atom);
+            }
+            None => push_utf16(data, self.class.name()),
+        }
+
+        push_utf16(data, &self.text);
+        push_u16(data, 0); // No creation data
+    }
+}
+
+/// Window class of a dialog control.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ControlClass {
+    Button,
+    Edit,
+    Static,
+    ListBox,
+    ScrollBar,
+    ComboBox,
+    /// Any other registered window class. (ex: `SysListView32`)
+    Named(String),
+}
+
+impl ControlClass {
+    /// Name of the window class.
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Button => "Button",
+            Self::Edit => "Edit",
+            Self::Static => "Static",
+            Self::ListBox => "ListBox",
+            Self::ScrollBar => "ScrollBar",
+            Self::ComboBox => "ComboBox",
+            Self::Named(name) => name,
+        }
+    }
+
+    /// Atom of the predefined system classes.
+    fn atom(&self) -> Option<u16> {
+        match self {
+            Self::Button => Some(0x0080),
+            Self::Edit => Some(0x0081),
+            Self::Static => Some(0x0082),
+            Self::ListBox => Some(0x0083),
+            Self::ScrollBar => Some(0x0084),
+            Self::ComboBox => Some(0x0085),
+            Self::Named(_) => None,
+        }
+    }
+}
+
+impl From<&str> for ControlClass {
+    fn from(value: &str) -> Self {
+        Self::Named(value.to_string())
+    }
+}
+
+impl From<String> for ControlClass {
+    fn from(value: String) -> Self {
+        Self::Named(value)
+    }
+}
@@ -0,0 +1,303 @@
+//! Dialog box resource. ([`DIALOGEX`](crate::dialog::Dialog))
+
+mod control;
+mod style;
+
+pub use control::*;
+pub use style::*;
+
+use crate::custom::ResType;
+use crate::util::{self, push_u16, push_u32, push_utf16};
+use crate::{ResError, ResId, ResWriter, Resource};
+
+/// Id of controls which are not referenced. (`IDC_STATIC`)
+const IDC_STATIC: u16 = 0xFFFF;
+
+/// Dialog box resource. (`DIALOGEX`)
+///
+/// Defines a dialog box template which can be created at runtime with `DialogBoxParamW` or
+/// `CreateDialogParamW`. All positions and sizes are in dialog units.
+///
+/// ### Example:
+/// ```
+/// # use winscribe::dialog::{Control, Dialog, DialogFont, Rect};
+/// Dialog::new("ABOUT", 200, 80)
+///     .with_caption("About")
+///     .with_font(DialogFont::new("Segoe UI", 9))
+///     .with_control(Control::label(100, "My App v1.0", Rect::new(10, 10, 180, 10)))
+///     .with_control(Control::default_button(1, "OK", Rect::new(140, 56, 50, 14)));
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/dialogex-resource>
+#[derive(Debug, Clone)]
+pub struct Dialog {
+    id: ResId,
+    language: Option<u16>,
+    /// Position and size of the dialog box in dialog units.
+    pub rect: Rect,
+    /// Title of the dialog box. Adds the `WS_CAPTION` style when set.
+    pub caption: Option<String>,
+    /// Font of the dialog box. Adds the `DS_SETFONT` style when set.
+    pub font: Option<DialogFont>,
+    /// Style of the dialog box.
+    pub style: Style,
+    /// Extended style of the dialog box.
+    pub ex_style: ExStyle,
+    /// Context help identifier.
+    pub help_id: u32,
+    /// Controls of the dialog box.
+    pub controls: Vec<Control>,
+}
+
+impl Dialog {
+    /// Creates a new modal dialog box. (`DS_MODALFRAME | WS_POPUP | WS_SYSMENU`)
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `width`, `height`: Size of the dialog box in dialog units.
+    pub fn new<N: Into<ResId>>(id: N, width: i16, height: i16) -> Self {
+        Self {
+            id: id.into(),
+            language: None,
+            rect: Rect::new(0, 0, width, height),
+            caption: None,
+            font: None,
+            style: Style::DS_MODALFRAME | Style::WS_POPUP | Style::WS_SYSMENU,
+            ex_style: ExStyle::default(),
+            help_id: 0,
+            controls: Vec::new(),
+        }
+    }
+
+    /// Sets the title of the dialog box.
+    pub fn with_caption<T: Into<String>>(mut self, caption: T) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+
+    /// Sets the font of the dialog box.
+    pub fn with_font(mut self, font: DialogFont) -> Self {
+        self.font = Some(font);
+        self
+    }
+
+    /// Sets the position of the dialog box relative to its owner.
+    pub fn with_position(mut self, x: i16, y: i16) -> Self {
+        self.rect.x = x;
+        self.rect.y = y;
+        self
+    }
+
+    /// Adds style flags to the dialog box.
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style |= style;
+        self
+    }
+
+    /// Adds extended style flags to the dialog box.
+    pub fn with_ex_style(mut self, ex_style: ExStyle) -> Self {
+        self.ex_style |= ex_style;
+        self
+    }
+
+    /// Adds a control to the dialog box.
+    pub fn with_control(mut self, control: Control) -> Self {
+        self.controls.push(control);
+        self
+    }
+
+    /// Style of the dialog box including the ones implied by the caption and the font.
+    fn full_style(&self) -> Style {
+        let mut style = self.style;
+        if self.caption.is_some() {
+            style |= Style::WS_CAPTION;
+        }
+        if self.font.is_some() {
+            style |= Style::DS_SETFONT;
+        }
+        style
+    }
+
+    /// Encodes the dialog box as a binary `DLGTEMPLATEEX`, the way it is stored in a compiled
+    /// resource. It can also be used with `CreateDialogIndirectParamW`.
+    pub fn to_template(&self) -> Result<Vec<u8>, ResError> {
+        let count = u16::try_from(self.controls.len())
+            .map_err(|_| ResError::Custom("Too many controls in the dialog box!"))?;
+
+        let mut data = Vec::with_capacity(256);
+        push_u16(&mut data, 1); // Version
+        push_u16(&mut data, 0xFFFF); // Signature
+        push_u32(&mut data, self.help_id);
+        push_u32(&mut data, self.ex_style.0);
+        push_u32(&mut data, self.full_style().0);
+        push_u16(&mut data, count);
+        self.rect.encode(&mut data);
+        push_u16(&mut data, 0); // No menu
+        push_u16(&mut data, 0); // Default window class
+        push_utf16(&mut data, self.caption.as_deref().unwrap_or_default());
+
+        if let Some(font) = &self.font {
+            push_u16(&mut data, font.size);
+            push_u16(&mut data, font.weight);
+            data.push(font.italic.into());
+            data.push(font.charset);
+            push_utf16(&mut data, &font.typeface);
+        }
+
+        for control in &self.controls {
+            control.encode(&mut data);
+        }
+
+        Ok(data)
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+}
+
+impl Resource for Dialog {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        writer.define(ResType::DIALOG, self.id.clone(), self.language)?;
+        writer.commands(
+            &self.id,
+            "Dialog",
+            "Control",
+            self.controls
+                .iter()
+                .filter(|control| control.id != IDC_STATIC)
+                .map(|control| (control.text.as_str(), control.id)),
+            self.language,
+        )?;
+        writer.line(format!(
+            "{} DIALOGEX {}, {}, {}, {}, {}",
+            self.id, self.rect.x, self.rect.y, self.rect.width, self.rect.height, self.help_id
+        ));
+        writer.line(format!("STYLE {:#010X}", self.full_style().0));
+        writer.line(format!("EXSTYLE {:#X}", self.ex_style.0));
+
+        if let Some(caption) = &self.caption {
+            writer.line(format!("CAPTION \"{}\"", util::escape(caption)));
+        }
+        if let Some(font) = &self.font {
+            writer.line(format!(
+                "FONT {}, \"{}\", {}, {}, {:#X}",
+                font.size,
+                util::escape(&font.typeface),
+                font.weight,
+                u8::from(font.italic),
+                font.charset
+            ));
+        }
+
+        writer.begin();
+        for control in &self.controls {
+            control.write(writer);
+        }
+        writer.end();
+
+        Ok(())
+    }
+}
+
+/// Position and size in dialog units.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i16,
+    pub y: i16,
+    pub width: i16,
+    pub height: i16,
+}
+
+impl Rect {
+    pub fn new(x: i16, y: i16, width: i16, height: i16) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+
+    fn encode(&self, data: &mut Vec<u8>) {
+        for value in [self.x, self.y, self.width, self.height] {
+            data.extend(value.to_le_bytes());
+        }
+    }
+}
+
+/// Font of a dialog box.
+#[derive(Debug, Clone)]
+pub struct DialogFont {
+    /// Name of the typeface. (ex: `Segoe UI`, `MS Shell Dlg`)
+    pub typeface: String,
+    /// Size in points.
+    pub size: u16,
+    /// Weight of the font. (ex: 400 for normal, 700 for bold)
+    pub weight: u16,
+    /// Use the italic variant.
+    pub italic: bool,
+    /// Character set. (`DEFAULT_CHARSET` by default)
+    pub charset: u8,
+}
+
+impl DialogFont {
+    pub fn new<T: Into<String>>(typeface: T, size: u16) -> Self {
+        Self {
+            typeface: typeface.into(),
+            size,
+            weight: 400,
+            italic: false,
+            charset: 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template() {
+        let dialog = Dialog::new(1, 100, 50)
+            .with_caption("A")
+            .with_control(Control::button(1, "OK", Rect::new(0, 0, 10, 10)));
+        let data = dialog.to_template().unwrap();
+
+        // Header: 26 bytes + menu (2), class (2), caption "A\0" (4) = 34 bytes
+        // Item aligned at 36: 24 bytes + class atom (4) + "OK\0" (6) + extra count (2)
+        assert_eq!(data.len(), 36 + 36);
+        assert_eq!(&data[..4], &[1, 0, 0xFF, 0xFF]);
+        assert_eq!(&data[36 + 24..36 + 28], &[0xFF, 0xFF, 0x80, 0x00]);
+    }
+
+    #[test]
+    fn dialog_text() {
+        let dialog = Dialog::new("ABOUT", 200, 80)
+            .with_caption("About \"My App\"")
+            .with_font(DialogFont::new("Segoe UI", 9))
+            .with_control(Control::label(
+                IDC_STATIC,
+                "C:\\Apps",
+                Rect::new(10, 10, 180, 10),
+            ))
+            .with_control(Control::default_button(1, "OK", Rect::new(140, 56, 50, 14)));
+
+        let mut writer = ResWriter::new();
+        dialog.write(&mut writer).unwrap();
+        let output = String::from_utf8_lossy(writer.as_bytes()).into_owned();
+        // WS_POPUP | WS_CAPTION | WS_SYSMENU | DS_MODALFRAME | DS_SETFONT
+        assert!(output.ends_with(
+            "ABOUT DIALOGEX 0, 0, 200, 80, 0\n\
+             STYLE 0x80C800C0\n\
+             EXSTYLE 0x0\n\
+             CAPTION \"About \"\"My App\"\"\"\n\
+             FONT 9, \"Segoe UI\", 400, 0, 0x1\n\
+             {\n\
+             \tCONTROL \"C:\\\\Apps\", 65535, \"Static\", 0x50020000, 10, 10, 180, 10, 0x0, 0\n\
+             \tCONTROL \"OK\", 1, \"Button\", 0x50010001, 140, 56, 50, 14, 0x0, 0\n\
+             }\n"
+        ));
+    }
+}
@@ -0,0 +1,154 @@
+/// Window and control style flags. (`WS_*`, `DS_*`, `BS_*`, ...)
+///
+/// Flags can be combined with the `|` operator.
+///
+/// More info: <https://learn.microsoft.com/windows/win32/winmsg/window-styles>
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style(pub u32);
+
+impl Style {
+    pub const WS_OVERLAPPED: Self = Self(0x00000000);
+    pub const WS_POPUP: Self = Self(0x80000000);
+    pub const WS_CHILD: Self = Self(0x40000000);
+    pub const WS_MINIMIZE: Self = Self(0x20000000);
+    pub const WS_VISIBLE: Self = Self(0x10000000);
+    pub const WS_DISABLED: Self = Self(0x08000000);
+    pub const WS_CLIPSIBLINGS: Self = Self(0x04000000);
+    pub const WS_CLIPCHILDREN: Self = Self(0x02000000);
+    pub const WS_MAXIMIZE: Self = Self(0x01000000);
+    pub const WS_CAPTION: Self = Self(0x00C00000);
+    pub const WS_BORDER: Self = Self(0x00800000);
+    pub const WS_DLGFRAME: Self = Self(0x00400000);
+    pub const WS_VSCROLL: Self = Self(0x00200000);
+    pub const WS_HSCROLL: Self = Self(0x00100000);
+    pub const WS_SYSMENU: Self = Self(0x00080000);
+    pub const WS_THICKFRAME: Self = Self(0x00040000);
+    pub const WS_GROUP: Self = Self(0x00020000);
+    pub const WS_TABSTOP: Self = Self(0x00010000);
+    pub const WS_MINIMIZEBOX: Self = Self(0x00020000);
+    pub const WS_MAXIMIZEBOX: Self = Self(0x00010000);
+
+    pub const DS_ABSALIGN: Self = Self(0x0001);
+    pub const DS_SYSMODAL: Self = Self(0x0002);
+    pub const DS_3DLOOK: Self = Self(0x0004);
+    pub const DS_FIXEDSYS: Self = Self(0x0008);
+    pub const DS_NOFAILCREATE: Self = Self(0x0010);
+    pub const DS_LOCALEDIT: Self = Self(0x0020);
+    pub const DS_SETFONT: Self = Self(0x0040);
+    pub const DS_MODALFRAME: Self = Self(0x0080);
+    pub const DS_NOIDLEMSG: Self = Self(0x0100);
+    pub const DS_SETFOREGROUND: Self = Self(0x0200);
+    pub const DS_CONTROL: Self = Self(0x0400);
+    pub const DS_CENTER: Self = Self(0x0800);
+    pub const DS_CENTERMOUSE: Self = Self(0x1000);
+    pub const DS_CONTEXTHELP: Self = Self(0x2000);
+    pub const DS_SHELLFONT: Self = Self(0x0048);
+
+    pub const BS_PUSHBUTTON: Self = Self(0x0000);
+    pub const BS_DEFPUSHBUTTON: Self = Self(0x0001);
+    pub const BS_CHECKBOX: Self = Self(0x0002);
+    pub const BS_AUTOCHECKBOX: Self = Self(0x0003);
+    pub const BS_RADIOBUTTON: Self = Self(0x0004);
+    pub const BS_3STATE: Self = Self(0x0005);
+    pub const BS_AUTO3STATE: Self = Self(0x0006);
+    pub const BS_GROUPBOX: Self = Self(0x0007);
+    pub const BS_AUTORADIOBUTTON: Self = Self(0x0009);
+
+    pub const ES_LEFT: Self = Self(0x0000);
+    pub const ES_CENTER: Self = Self(0x0001);
+    pub const ES_RIGHT: Self = Self(0x0002);
+    pub const ES_MULTILINE: Self = Self(0x0004);
+    pub const ES_UPPERCASE: Self = Self(0x0008);
+    pub const ES_LOWERCASE: Self = Self(0x0010);
+    pub const ES_PASSWORD: Self = Self(0x0020);
+    pub const ES_AUTOVSCROLL: Self = Self(0x0040);
+    pub const ES_AUTOHSCROLL: Self = Self(0x0080);
+    pub const ES_NOHIDESEL: Self = Self(0x0100);
+    pub const ES_READONLY: Self = Self(0x0800);
+    pub const ES_WANTRETURN: Self = Self(0x1000);
+    pub const ES_NUMBER: Self = Self(0x2000);
+
+    pub const SS_LEFT: Self = Self(0x0000);
+    pub const SS_CENTER: Self = Self(0x0001);
+    pub const SS_RIGHT: Self = Self(0x0002);
+    pub const SS_ICON: Self = Self(0x0003);
+    pub const SS_NOPREFIX: Self = Self(0x0080);
+
+    pub const CBS_SIMPLE: Self = Self(0x0001);
+    pub const CBS_DROPDOWN: Self = Self(0x0002);
+    pub const CBS_DROPDOWNLIST: Self = Self(0x0003);
+    pub const CBS_AUTOHSCROLL: Self = Self(0x0040);
+    pub const CBS_SORT: Self = Self(0x0100);
+    pub const CBS_HASSTRINGS: Self = Self(0x0200);
+
+    pub const LBS_NOTIFY: Self = Self(0x0001);
+    pub const LBS_SORT: Self = Self(0x0002);
+    pub const LBS_MULTIPLESEL: Self = Self(0x0008);
+    pub const LBS_NOINTEGRALHEIGHT: Self = Self(0x0100);
+
+    pub const LVS_ICON: Self = Self(0x0000);
+    pub const LVS_REPORT: Self = Self(0x0001);
+    pub const LVS_SMALLICON: Self = Self(0x0002);
+    pub const LVS_LIST: Self = Self(0x0003);
+    pub const LVS_SINGLESEL: Self = Self(0x0004);
+    pub const LVS_SHOWSELALWAYS: Self = Self(0x0008);
+    pub const LVS_NOSORTHEADER: Self = Self(0x8000);
+}
+
+impl std::ops::BitOr for Style {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for Style {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+/// Extended window style flags. (`WS_EX_*`)
+///
+/// Flags can be combined with the `|` operator.
+///
+/// More info: <https://learn.microsoft.com/windows/win32/winmsg/extended-window-styles>
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExStyle(pub u32);
+
+impl ExStyle {
+    pub const WS_EX_DLGMODALFRAME: Self = Self(0x00000001);
+    pub const WS_EX_NOPARENTNOTIFY: Self = Self(0x00000004);
+    pub const WS_EX_TOPMOST: Self = Self(0x00000008);
+    pub const WS_EX_ACCEPTFILES: Self = Self(0x00000010);
+    pub const WS_EX_TRANSPARENT: Self = Self(0x00000020);
+    pub const WS_EX_TOOLWINDOW: Self = Self(0x00000080);
+    pub const WS_EX_WINDOWEDGE: Self = Self(0x00000100);
+    pub const WS_EX_CLIENTEDGE: Self = Self(0x00000200);
+    pub const WS_EX_CONTEXTHELP: Self = Self(0x00000400);
+    pub const WS_EX_RIGHT: Self = Self(0x00001000);
+    pub const WS_EX_RTLREADING: Self = Self(0x00002000);
+    pub const WS_EX_LEFTSCROLLBAR: Self = Self(0x00004000);
+    pub const WS_EX_CONTROLPARENT: Self = Self(0x00010000);
+    pub const WS_EX_STATICEDGE: Self = Self(0x00020000);
+    pub const WS_EX_APPWINDOW: Self = Self(0x00040000);
+    pub const WS_EX_LAYERED: Self = Self(0x00080000);
+    pub const WS_EX_LAYOUTRTL: Self = Self(0x00400000);
+    pub const WS_EX_COMPOSITED: Self = Self(0x02000000);
+    pub const WS_EX_NOACTIVATE: Self = Self(0x08000000);
+}
+
+impl std::ops::BitOr for ExStyle {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl std::ops::BitOrAssign for ExStyle {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
@@ -0,0 +1,374 @@
+//! Encoder of the binary event template. (`CRIM` blob of the `WEVT_TEMPLATE` resource)
+//!
+//! The layout follows the libfwevt documentation of the Windows Event manifest binary format.
+
+use super::MessageIds;
+use super::parse::{Provider, Template};
+use crate::ResError;
+use crate::util::{push_u16, push_u32, sha1};
+
+/// Encodes the providers and assigns the ids of the messages they reference.
+pub(super) fn encode(
+    providers: &[Provider],
+    messages: &mut MessageIds,
+) -> Result<Vec<u8>, ResError> {
+    let mut data = Vec::new();
+    data.extend(b"CRIM");
+    push_u32(&mut data, 0); // Size, set below
+    push_u16(&mut data, 3); // Major version
+    push_u16(&mut data, 1); // Minor version
+    push_u32(&mut data, providers.len() as u32);
+
+    let descriptors = data.len();
+    for provider in providers {
+        data.extend(provider.guid);
+        push_u32(&mut data, 0); // Offset, set below
+    }
+
+    for (index, provider) in providers.iter().enumerate() {
+        let offset = data.len() as u32;
+        set_u32(&mut data, descriptors + index * 20 + 16, offset);
+        encode_provider(&mut data, provider, messages)?;
+    }
+
+    let size = data.len() as u32;
+    set_u32(&mut data, 4, size);
+    Ok(data)
+}
+
+fn encode_provider(
+    data: &mut Vec<u8>,
+    provider: &Provider,
+    messages: &mut MessageIds,
+) -> Result<(), ResError> {
+    // Elements are only written when the provider defines them, except the events
+    let elements = [
+        !provider.channels.is_empty(),
+        !provider.levels.is_empty(),
+        !provider.opcodes.is_empty(),
+        !provider.tasks.is_empty(),
+        !provider.keywords.is_empty(),
+        !provider.templates.is_empty(),
+        true,
+    ];
+    let count = elements.iter().filter(|written| **written).count();
+
+    data.extend(b"WEVT");
+    push_u32(data, 20 + 8 * count as u32);
+    push_u32(data, messages.id(&provider.message)?);
+    push_u32(data, count as u32);
+    push_u32(data, 0); // Number of unknown2 entries
+
+    let mut descriptors = data.len();
+    data.resize(descriptors + 8 * count, 0);
+    let mut next_element = |data: &mut Vec<u8>| {
+        let offset = data.len() as u32;
+        set_u32(data, descriptors, offset);
+        descriptors += 8;
+    };
+
+    if elements[0] {
+        next_element(data);
+        let table = begin_table(data, b"CHAN", provider.channels.len(), 16);
+        let mut names = Names::new(table + 12 + provider.channels.len() * 16);
+        for channel in &provider.channels {
+            push_u32(data, u32::from(channel.value));
+            push_u32(data, names.add(&channel.name));
+            push_u32(data, 0);
+            push_u32(data, messages.id(&channel.message)?);
+        }
+        names.finish(data, table);
+    }
+
+    let mut levels = Vec::with_capacity(provider.levels.len());
+    if elements[1] {
+        next_element(data);
+        let table = begin_table(data, b"LEVL", provider.levels.len(), 12);
+        let mut names = Names::new(table + 12 + provider.levels.len() * 12);
+        for level in &provider.levels {
+            levels.push(data.len() as u32);
+            push_u32(data, u32::from(level.value));
+            push_u32(data, messages.id_or(&level.message, level.winmeta)?);
+            push_u32(data, names.add(&level.name));
+        }
+        names.finish(data, table);
+    }
+
+    let mut opcodes = Vec::with_capacity(provider.opcodes.len());
+    if elements[2] {
+        next_element(data);
+        let table = begin_table(data, b"OPCO", provider.opcodes.len(), 12);
+        let mut names = Names::new(table + 12 + provider.opcodes.len() * 12);
+        for opcode in &provider.opcodes {
+            opcodes.push(data.len() as u32);
+            push_u32(data, u32::from(opcode.task) << 16 | u32::from(opcode.value));
+            push_u32(data, messages.id_or(&opcode.message, opcode.winmeta)?);
+            push_u32(data, names.add(&opcode.name));
+        }
+        names.finish(data, table);
+    }
+
+    let mut tasks = Vec::with_capacity(provider.tasks.len());
+    if elements[3] {
+        next_element(data);
+        let table = begin_table(data, b"TASK", provider.tasks.len(), 28);
+        let mut names = Names::new(table + 12 + provider.tasks.len() * 28);
+        for task in &provider.tasks {
+            tasks.push(data.len() as u32);
+            push_u32(data, u32::from(task.value));
+            push_u32(data, messages.id(&task.message)?);
+            data.extend(task.guid);
+            push_u32(data, names.add(&task.name));
+        }
+        names.finish(data, table);
+    }
+
+    if elements[4] {
+        next_element(data);
+        let table = begin_table(data, b"KEYW", provider.keywords.len(), 16);
+        let mut names = Names::new(table + 12 + provider.keywords.len() * 16);
+        for keyword in &provider.keywords {
+            data.extend(keyword.mask.to_le_bytes());
+            push_u32(data, messages.id(&keyword.message)?);
+            push_u32(data, names.add(&keyword.name));
+        }
+        names.finish(data, table);
+    }
+
+    let mut templates = Vec::with_capacity(provider.templates.len());
+    if elements[5] {
+        next_element(data);
+        let table = data.len();
+        data.extend(b"TTBL");
+        push_u32(data, 0); // Size, set below
+        push_u32(data, provider.templates.len() as u32);
+        for template in &provider.templates {
+            templates.push(data.len() as u32);
+            encode_template(data, &provider.guid, template)?;
+        }
+        let size = (data.len() - table) as u32;
+        set_u32(data, table + 4, size);
+    }
+
+    next_element(data);
+    data.extend(b"EVNT");
+    push_u32(data, (16 + provider.events.len() * 48) as u32);
+    push_u32(data, provider.events.len() as u32);
+    push_u32(data, 0);
+    for event in &provider.events {
+        push_u16(data, event.value);
+        data.push(event.version);
+        data.push(event.channel);
+        data.push(event.level);
+        data.push(event.opcode);
+        push_u16(data, event.task);
+        data.extend(event.keywords.to_le_bytes());
+        push_u32(data, messages.id(&event.message)?);
+        push_u32(data, event.template.map_or(0, |index| templates[index]));
+        push_u32(data, event.opcode_def.map_or(0, |index| opcodes[index]));
+        push_u32(data, event.level_def.map_or(0, |index| levels[index]));
+        push_u32(data, event.task_def.map_or(0, |index| tasks[index]));
+        push_u32(data, 0); // Number of unknown entries
+        push_u32(data, 0); // Unknown entries offset
+        push_u32(data, 0); // Flags
+    }
+    Ok(())
+}
+
+fn encode_template(
+    data: &mut Vec<u8>,
+    provider: &[u8; 16],
+    template: &Template,
+) -> Result<(), ResError> {
+    let start = data.len();
+    let count = template.items.len() as u32;
+    data.extend(b"TEMP");
+    push_u32(data, 0); // Size, set below
+    push_u32(data, count);
+    push_u32(data, count);
+    push_u32(data, 0); // Items offset, set below
+    push_u32(data, 1); // Event type: EventData
+    data.extend(template_guid(provider, &template.tid));
+
+    encode_binxml(data, template)?;
+
+    let items = data.len();
+    set_u32(data, start + 16, if count == 0 { 0 } else { items as u32 });
+
+    let mut names = items + template.items.len() * 20;
+    for item in &template.items {
+        push_u32(data, 0);
+        data.push(item.in_type);
+        data.push(item.out_type);
+        push_u16(data, 0);
+        push_u32(data, 0);
+        push_u16(data, item.count);
+        push_u16(data, item.length);
+        push_u32(data, names as u32);
+        names += name_size(&item.name);
+    }
+    for item in &template.items {
+        push_name(data, &item.name);
+    }
+
+    let size = (data.len() - start) as u32;
+    set_u32(data, start + 4, size);
+    Ok(())
+}
+
+/// Encodes the template's XML: `<EventData><Data Name="...">%n</Data>...</EventData>`
+fn encode_binxml(data: &mut Vec<u8>, template: &Template) -> Result<(), ResError> {
+    // Fragment header
+    data.extend([0x0F, 0x01, 0x01, 0x00]);
+
+    let event_data = open_element(data, "EventData", false);
+    data.push(0x02); // Close start element
+
+    for (index, item) in template.items.iter().enumerate() {
+        let index = u16::try_from(index)
+            .map_err(|_| ResError::Custom("Event template has too many items!"))?;
+
+        let element = open_element(data, "Data", true);
+        let attributes = data.len();
+        push_u32(data, 0); // Attribute list size, set below
+        data.push(0x06); // Attribute
+        push_inline_name(data, "Name");
+        data.push(0x05); // Value text
+        data.push(0x01); // String type
+        let name: Vec<u16> = item.name.encode_utf16().collect();
+        push_u16(data, name.len() as u16);
+        for unit in name {
+            push_u16(data, unit);
+        }
+        let size = (data.len() - attributes - 4) as u32;
+        set_u32(data, attributes, size);
+        data.push(0x02); // Close start element
+
+        // Optional substitution. Arrays are marked with the 0x80 flag of the value type.
+        data.push(0x0E);
+        push_u16(data, index);
+        data.push(if item.count > 1 {
+            item.in_type | 0x80
+        } else {
+            item.in_type
+        });
+
+        data.push(0x04); // End element
+        close_element(data, element);
+    }
+
+    data.push(0x04); // End element
+    close_element(data, event_data);
+
+    data.push(0x00); // End of stream
+    Ok(())
+}
+
+/// Writes the open start element token and returns the position of its data size.
+fn open_element(data: &mut Vec<u8>, name: &str, attributes: bool) -> usize {
+    data.push(if attributes { 0x41 } else { 0x01 });
+    push_u16(data, 0xFFFF); // Dependency identifier
+    let size = data.len();
+    push_u32(data, 0); // Data size, set by close_element
+    push_inline_name(data, name);
+    size
+}
+
+fn close_element(data: &mut [u8], size: usize) {
+    let length = (data.len() - size - 4) as u32;
+    set_u32(data, size, length);
+}
+
+/// Writes an element name as `hash, character count, UTF-16, NUL`.
+fn push_inline_name(data: &mut Vec<u8>, name: &str) {
+    let units: Vec<u16> = name.encode_utf16().collect();
+    let hash = units.iter().fold(0u32, |hash, unit| {
+        hash.wrapping_mul(65599).wrapping_add(u32::from(*unit))
+    });
+    push_u16(data, hash as u16);
+    push_u16(data, units.len() as u16);
+    for unit in units {
+        push_u16(data, unit);
+    }
+    push_u16(data, 0);
+}
+
+/// Template GUIDs are not part of the manifest so they are derived from the template id,
+/// as a name-based (version 5) UUID in the namespace of the provider's GUID.
+fn template_guid(provider: &[u8; 16], tid: &str) -> [u8; 16] {
+    // GUIDs store the first three fields little-endian, UUIDs big-endian
+    let swap = |guid: &mut [u8]| {
+        guid[..4].reverse();
+        guid[4..6].reverse();
+        guid[6..8].reverse();
+    };
+
+    let mut name = provider.to_vec();
+    swap(&mut name);
+    name.extend(tid.as_bytes());
+
+    let mut guid: [u8; 16] = sha1(&name)[..16].try_into().unwrap();
+    guid[6] = (guid[6] & 0x0F) | 0x50; // Version
+    guid[8] = (guid[8] & 0x3F) | 0x80; // Variant
+    swap(&mut guid);
+    guid
+}
+
+/// Writes a table header and returns its position.
+fn begin_table(data: &mut Vec<u8>, signature: &[u8; 4], count: usize, record: usize) -> usize {
+    let start = data.len();
+    data.extend(signature);
+    push_u32(data, (12 + count * record) as u32);
+    push_u32(data, count as u32);
+    start
+}
+
+/// Names stored after the records of a table.
+struct Names<'a> {
+    offset: usize,
+    names: Vec<&'a str>,
+}
+
+impl<'a> Names<'a> {
+    fn new(offset: usize) -> Self {
+        Self {
+            offset,
+            names: Vec::new(),
+        }
+    }
+
+    /// Queues the name and returns its offset.
+    fn add(&mut self, name: &'a str) -> u32 {
+        let offset = self.offset as u32;
+        self.offset += name_size(name);
+        self.names.push(name);
+        offset
+    }
+
+    /// Writes the names and updates the size of the table.
+    fn finish(self, data: &mut Vec<u8>, table: usize) {
+        for name in self.names {
+            push_name(data, name);
+        }
+        let size = (data.len() - table) as u32;
+        set_u32(data, table + 4, size);
+    }
+}
+
+/// Size of a name including its size field, padded to 4 bytes.
+fn name_size(name: &str) -> usize {
+    (4 + (name.encode_utf16().count() + 1) * 2).next_multiple_of(4)
+}
+
+/// Writes a name as `size, UTF-16, NUL`.
+fn push_name(data: &mut Vec<u8>, name: &str) {
+    let size = name_size(name);
+    let start = data.len();
+    push_u32(data, size as u32);
+    crate::util::push_utf16(data, name);
+    data.resize(start + size, 0);
+}
+
+fn set_u32(data: &mut [u8], offset: usize, value: u32) {
+    data[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+}
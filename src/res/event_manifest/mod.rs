@@ -0,0 +1,280 @@
+//! Event manifest resource. ([`WEVT_TEMPLATE`](crate::event_manifest::EventManifest))
+
+mod binary;
+mod parse;
+
+use crate::message_table::{Message, MessageTable};
+use crate::{ResError, ResWriter, Resource, util};
+use std::path::PathBuf;
+
+/// Message id written when an element has no message.
+const NO_MESSAGE: u32 = 0xFFFF_FFFF;
+
+/// Event manifest resource. (`WEVT_TEMPLATE` with its `MESSAGETABLE`)
+///
+/// Compiles an ETW instrumentation manifest (`.man`) into the binary event template and
+/// the message table of its localized strings without the message compiler (`mc.exe -um`).
+/// The executable can then be registered as the provider's resource and message file with
+/// `wevtutil im`.
+///
+/// Only one message table can be embedded, so messages reported with `ReportEventW` need to be
+/// added with [`with_messages`](Self::with_messages) instead of a separate [`MessageTable`].
+///
+/// ### Example:
+/// ```
+/// # use winscribe::event_manifest::EventManifest;
+/// EventManifest::new("service.man");
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/wes/writing-an-instrumentation-manifest>
+#[derive(Debug, Clone)]
+pub struct EventManifest {
+    path: PathBuf,
+    messages: MessageTable,
+}
+
+impl EventManifest {
+    /// Creates a new event manifest resource from the instrumentation manifest's path.
+    pub fn new<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            path: path.into(),
+            messages: MessageTable::new(),
+        }
+    }
+
+    /// Sets the message table the manifest's strings are added to.
+    ///
+    /// Strings of the manifest are assigned ids with the customer bit set, starting from 1.
+    pub fn with_messages(mut self, messages: MessageTable) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// Compiles the manifest into the binary event template and the message table.
+    ///
+    /// * `default`: Language of the default text if the message table doesn't set one.
+    fn compile(&self, default: u16) -> Result<(Vec<u8>, MessageTable), ResError> {
+        let mut messages = self.messages.clone();
+        messages.language.get_or_insert(default);
+        compile(&std::fs::read_to_string(&self.path)?, messages)
+    }
+}
+
+impl Resource for EventManifest {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        let (template, messages) = self.compile(writer.default_language())?;
+        let path = util::hashed_out_file("wevt", "bin", &template)?;
+        writer.define("WEVT_TEMPLATE", 1, None)?;
+        writer.line(format!("1 WEVT_TEMPLATE \"{}\"", util::escape_path(path)?));
+        messages.write(writer)
+    }
+}
+
+fn compile(xml: &str, mut table: MessageTable) -> Result<(Vec<u8>, MessageTable), ResError> {
+    let manifest = parse::parse(xml)?;
+
+    // Default text comes from the table's language, or the first string table if missing
+    let default = manifest
+        .resources
+        .iter()
+        .position(|resources| Some(resources.language) == table.language)
+        .unwrap_or(0);
+    if let Some(resources) = manifest.resources.get(default) {
+        table.language = Some(resources.language);
+    }
+
+    let mut messages = MessageIds {
+        manifest: &manifest,
+        default,
+        next: 1,
+        assigned: Vec::new(),
+        messages: Vec::new(),
+    };
+    let template = binary::encode(&manifest.providers, &mut messages)?;
+
+    table.messages.extend(messages.messages);
+    Ok((template, table))
+}
+
+/// Assigns message ids to the strings referenced by the manifest.
+struct MessageIds<'a> {
+    manifest: &'a parse::Instrumentation,
+    /// Index of the string table with the default text.
+    default: usize,
+    next: u16,
+    assigned: Vec<(String, u32)>,
+    messages: Vec<Message>,
+}
+
+impl MessageIds<'_> {
+    /// Message id of the string, or `0xFFFFFFFF` when there is no message.
+    fn id(&mut self, string: &Option<String>) -> Result<u32, ResError> {
+        self.id_or(string, None)
+    }
+
+    /// Message id of the string, or the `fallback` when there is no message.
+    fn id_or(&mut self, string: &Option<String>, fallback: Option<u32>) -> Result<u32, ResError> {
+        let Some(string) = string.as_deref() else {
+            return Ok(fallback.unwrap_or(NO_MESSAGE));
+        };
+
+        if let Some((_, id)) = self.assigned.iter().find(|(name, _)| *name == string) {
+            return Ok(*id);
+        }
+
+        let lookup = |index: usize| {
+            self.manifest.resources[index]
+                .strings
+                .iter()
+                .find(|(id, _)| id == string)
+                .map(|(_, text)| text.as_str())
+        };
+
+        let text = self
+            .manifest
+            .resources
+            .get(self.default)
+            .and_then(|_| lookup(self.default))
+            .ok_or_else(|| {
+                ResError::Parse(format!(
+                    "instrumentation manifest: string \"{string}\" is not defined"
+                ))
+            })?;
+
+        let mut message = Message::new(self.next, text);
+        message.customer = true;
+        for (index, resources) in self.manifest.resources.iter().enumerate() {
+            if index != self.default
+                && let Some(text) = lookup(index)
+            {
+                message = message.with_translation(resources.language, text);
+            }
+        }
+
+        let id = message.id()?;
+        self.next = self.next.checked_add(1).ok_or(ResError::Custom(
+            "Event manifest contains too many strings!",
+        ))?;
+        self.assigned.push((string.to_string(), id));
+        self.messages.push(message);
+        Ok(id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"<instrumentationManifest xmlns="http://schemas.microsoft.com/win/2004/08/events">
+  <instrumentation>
+    <events>
+      <provider name="Test-Provider" guid="{01234567-89AB-CDEF-0123-456789ABCDEF}"
+                symbol="TEST_PROVIDER" message="$(string.Provider)">
+        <channels>
+          <importChannel name="Application" chid="app"/>
+        </channels>
+        <templates>
+          <template tid="Port">
+            <data name="Port" inType="win:UInt16"/>
+          </template>
+        </templates>
+        <events>
+          <event value="1" channel="app" level="win:Informational" template="Port"
+                 message="$(string.Started)"/>
+        </events>
+      </provider>
+    </events>
+  </instrumentation>
+  <localization>
+    <resources culture="en-US">
+      <stringTable>
+        <string id="Provider" value="Test Provider"/>
+        <string id="Started" value="Started on port %1."/>
+      </stringTable>
+    </resources>
+    <resources culture="de-DE">
+      <stringTable>
+        <string id="Started" value="Auf Port %1 gestartet."/>
+      </stringTable>
+    </resources>
+  </localization>
+</instrumentationManifest>"#;
+
+    fn u32_at(data: &[u8], offset: usize) -> u32 {
+        u32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn template() {
+        let (data, table) = compile(MANIFEST, MessageTable::new()).unwrap();
+
+        assert_eq!(&data[..4], b"CRIM");
+        assert_eq!(u32_at(&data, 4) as usize, data.len());
+        assert_eq!(u32_at(&data, 12), 1);
+        assert_eq!(&data[16..20], &[0x67, 0x45, 0x23, 0x01]);
+
+        let provider = u32_at(&data, 32) as usize;
+        assert_eq!(&data[provider..provider + 4], b"WEVT");
+        assert_eq!(u32_at(&data, provider + 8), 0x2000_0001);
+        // CHAN, LEVL, TTBL and EVNT
+        assert_eq!(u32_at(&data, provider + 12), 4);
+
+        let events = u32_at(&data, provider + 20 + 3 * 8) as usize;
+        assert_eq!(&data[events..events + 4], b"EVNT");
+        assert_eq!(&data[events + 16..events + 22], &[1, 0, 0, 9, 4, 0]);
+        assert_eq!(u32_at(&data, events + 32), 0x2000_0002);
+
+        let template = u32_at(&data, events + 36) as usize;
+        assert_eq!(&data[template..template + 4], b"TEMP");
+        // Name-based UUID 175d563c-3fc0-5b8b-956a-299816884d52
+        assert_eq!(
+            &data[template + 24..template + 40],
+            &[
+                60, 86, 93, 23, 192, 63, 139, 91, 149, 106, 41, 152, 22, 136, 77, 82
+            ]
+        );
+
+        assert_eq!(table.messages.len(), 2);
+        assert_eq!(table.messages[1].translations[0].0, 0x0407);
+    }
+
+    #[test]
+    fn definitions_after_events() {
+        // Layout written by ECManGen
+        let provider = |channels: &str| {
+            format!(
+                r#"<instrumentationManifest xmlns="http://schemas.microsoft.com/win/2004/08/events">
+  <instrumentation>
+    <events>
+      <provider name="Test-Provider" guid="{{01234567-89AB-CDEF-0123-456789ABCDEF}}">
+        <events>
+          <event value="1" channel="ops" template="Port"/>
+        </events>
+        <channels>{channels}</channels>
+        <templates>
+          <template tid="Port">
+            <data name="Port" inType="win:UInt16"/>
+          </template>
+        </templates>
+      </provider>
+    </events>
+  </instrumentation>
+</instrumentationManifest>"#
+            )
+        };
+
+        let manifest = parse::parse(&provider(
+            r#"<channel name="Test/Admin" value="255" type="Admin"/><channel name="ops" chid="ops" type="Operational"/>"#,
+        ));
+        assert!(manifest.is_err());
+
+        let manifest = parse::parse(&provider(
+            r#"<channel name="Test/Admin" value="254" type="Admin"/><channel name="ops" chid="ops" type="Operational"/>"#,
+        ))
+        .unwrap();
+        let provider = &manifest.providers[0];
+        assert_eq!(provider.channels[1].value, 255);
+        assert_eq!(provider.events[0].channel, 255);
+        assert_eq!(provider.events[0].template, Some(0));
+    }
+}
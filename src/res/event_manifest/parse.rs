@@ -0,0 +1,615 @@
+//! Reads the providers and localized strings of an instrumentation manifest.
+
+use crate::ResError;
+use roxmltree::{Document, Node};
+
+pub(super) struct Provider {
+    pub guid: [u8; 16],
+    pub message: Option<String>,
+    pub channels: Vec<Channel>,
+    pub levels: Vec<Level>,
+    pub tasks: Vec<Task>,
+    pub opcodes: Vec<Opcode>,
+    pub keywords: Vec<Keyword>,
+    pub templates: Vec<Template>,
+    pub events: Vec<Event>,
+}
+
+pub(super) struct Channel {
+    pub name: String,
+    pub chid: String,
+    pub value: u8,
+    pub message: Option<String>,
+}
+
+pub(super) struct Level {
+    pub name: String,
+    pub value: u8,
+    pub message: Option<String>,
+    /// Message id of a predefined level in the system's `winmeta` message table.
+    pub winmeta: Option<u32>,
+}
+
+pub(super) struct Task {
+    pub name: String,
+    pub value: u16,
+    pub guid: [u8; 16],
+    pub message: Option<String>,
+}
+
+pub(super) struct Opcode {
+    pub name: String,
+    pub value: u8,
+    /// Task the opcode is defined in. 0 for provider-wide opcodes.
+    pub task: u16,
+    pub message: Option<String>,
+    /// Message id of a predefined opcode in the system's `winmeta` message table.
+    pub winmeta: Option<u32>,
+}
+
+pub(super) struct Keyword {
+    pub name: String,
+    pub mask: u64,
+    pub message: Option<String>,
+}
+
+pub(super) struct Template {
+    pub tid: String,
+    pub items: Vec<TemplateItem>,
+}
+
+pub(super) struct TemplateItem {
+    pub name: String,
+    pub in_type: u8,
+    pub out_type: u8,
+    pub count: u16,
+    pub length: u16,
+}
+
+pub(super) struct Event {
+    pub value: u16,
+    pub version: u8,
+    pub channel: u8,
+    pub level: u8,
+    pub opcode: u8,
+    pub task: u16,
+    pub keywords: u64,
+    pub message: Option<String>,
+    /// Index of the template.
+    pub template: Option<usize>,
+    /// Index of the level definition.
+    pub level_def: Option<usize>,
+    /// Index of the opcode definition.
+    pub opcode_def: Option<usize>,
+    /// Index of the task definition.
+    pub task_def: Option<usize>,
+}
+
+/// Localized strings of a culture.
+pub(super) struct Resources {
+    pub language: u16,
+    pub strings: Vec<(String, String)>,
+}
+
+pub(super) struct Instrumentation {
+    pub providers: Vec<Provider>,
+    pub resources: Vec<Resources>,
+}
+
+const LEVELS: &[(&str, u8)] = &[
+    ("win:LogAlways", 0),
+    ("win:Critical", 1),
+    ("win:Error", 2),
+    ("win:Warning", 3),
+    ("win:Informational", 4),
+    ("win:Verbose", 5),
+];
+
+const OPCODES: &[(&str, u8)] = &[
+    ("win:Info", 0),
+    ("win:Start", 1),
+    ("win:Stop", 2),
+    ("win:DC_Start", 3),
+    ("win:DC_Stop", 4),
+    ("win:Extension", 5),
+    ("win:Reply", 6),
+    ("win:Resume", 7),
+    ("win:Suspend", 8),
+    ("win:Send", 9),
+    ("win:Receive", 240),
+];
+
+const CHANNELS: &[(&str, u8)] = &[
+    ("TraceClassic", 0),
+    ("System", 8),
+    ("Application", 9),
+    ("Security", 10),
+];
+
+const IN_TYPES: &[(&str, u8)] = &[
+    ("win:UnicodeString", 1),
+    ("win:AnsiString", 2),
+    ("win:Int8", 3),
+    ("win:UInt8", 4),
+    ("win:Int16", 5),
+    ("win:UInt16", 6),
+    ("win:Int32", 7),
+    ("win:UInt32", 8),
+    ("win:Int64", 9),
+    ("win:UInt64", 10),
+    ("win:Float", 11),
+    ("win:Double", 12),
+    ("win:Boolean", 13),
+    ("win:Binary", 14),
+    ("win:GUID", 15),
+    ("win:Pointer", 16),
+    ("win:FILETIME", 17),
+    ("win:SYSTEMTIME", 18),
+    ("win:SID", 19),
+    ("win:HexInt32", 20),
+    ("win:HexInt64", 21),
+];
+
+const OUT_TYPES: &[(&str, u8)] = &[
+    ("xs:string", 1),
+    ("xs:dateTime", 2),
+    ("xs:byte", 3),
+    ("xs:unsignedByte", 4),
+    ("xs:short", 5),
+    ("xs:unsignedShort", 6),
+    ("xs:int", 7),
+    ("xs:unsignedInt", 8),
+    ("xs:long", 9),
+    ("xs:unsignedLong", 10),
+    ("xs:float", 11),
+    ("xs:double", 12),
+    ("xs:boolean", 13),
+    ("xs:hexBinary", 14),
+    ("xs:GUID", 15),
+    ("win:SID", 19),
+    ("win:HexInt32", 20),
+    ("win:HexInt64", 21),
+];
+
+pub(super) fn parse(xml: &str) -> Result<Instrumentation, ResError> {
+    let document = Document::parse(xml).map_err(|e| error(&e.to_string()))?;
+    let root = document.root_element();
+
+    let providers = root
+        .descendants()
+        .filter(|node| is(node, "provider"))
+        .map(parse_provider)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let resources = root
+        .descendants()
+        .filter(|node| is(node, "resources"))
+        .map(|node| {
+            let culture = attribute(&node, "culture")?;
+            let language =
+                lcid(culture).ok_or_else(|| error(&format!("unknown culture \"{culture}\"")))?;
+            let strings = node
+                .descendants()
+                .filter(|node| is(node, "string"))
+                .map(|node| Ok((attribute(&node, "id")?.to_string(), value(&node)?)))
+                .collect::<Result<Vec<_>, ResError>>()?;
+            Ok(Resources { language, strings })
+        })
+        .collect::<Result<Vec<_>, ResError>>()?;
+
+    if providers.is_empty() {
+        return Err(error("no event provider is defined"));
+    }
+
+    Ok(Instrumentation {
+        providers,
+        resources,
+    })
+}
+
+fn parse_provider(node: Node) -> Result<Provider, ResError> {
+    let mut provider = Provider {
+        guid: parse_guid(attribute(&node, "guid")?)?,
+        message: message(&node)?,
+        channels: Vec::new(),
+        levels: Vec::new(),
+        tasks: Vec::new(),
+        opcodes: Vec::new(),
+        keywords: Vec::new(),
+        templates: Vec::new(),
+        events: Vec::new(),
+    };
+
+    // Events may come before the definitions they reference
+    for list in node.children().filter(Node::is_element) {
+        let items = list.children().filter(Node::is_element);
+        match list.tag_name().name() {
+            "channels" => {
+                // Implicit values follow the largest value so far, starting from 16
+                let mut next_value: u16 = 16;
+                for item in items {
+                    let name = attribute(&item, "name")?.to_string();
+                    let value = match item.attribute("value") {
+                        Some(value) => number(value)?,
+                        None if is(&item, "importChannel") => lookup(CHANNELS, &name)
+                            .ok_or_else(|| error(&format!("unknown channel \"{name}\"")))?,
+                        None => u8::try_from(next_value).map_err(|_| {
+                            error(&format!("no channel value is left for \"{name}\""))
+                        })?,
+                    };
+                    next_value = next_value.max(u16::from(value) + 1);
+
+                    provider.channels.push(Channel {
+                        chid: item.attribute("chid").unwrap_or(&name).to_string(),
+                        name,
+                        value,
+                        message: message(&item)?,
+                    });
+                }
+            }
+            "levels" => {
+                for item in items {
+                    provider.levels.push(Level {
+                        name: attribute(&item, "name")?.to_string(),
+                        value: number(attribute(&item, "value")?)?,
+                        message: message(&item)?,
+                        winmeta: None,
+                    });
+                }
+            }
+            "tasks" => {
+                for item in items {
+                    let value = number(attribute(&item, "value")?)?;
+                    let guid = match item.attribute("eventGUID") {
+                        Some(guid) => parse_guid(guid)?,
+                        None => [0; 16],
+                    };
+
+                    for opcode in item.descendants().filter(|node| is(node, "opcode")) {
+                        provider.opcodes.push(parse_opcode(&opcode, value)?);
+                    }
+
+                    provider.tasks.push(Task {
+                        name: attribute(&item, "name")?.to_string(),
+                        value,
+                        guid,
+                        message: message(&item)?,
+                    });
+                }
+            }
+            "opcodes" => {
+                for item in items {
+                    provider.opcodes.push(parse_opcode(&item, 0)?);
+                }
+            }
+            "keywords" => {
+                for item in items {
+                    provider.keywords.push(Keyword {
+                        name: attribute(&item, "name")?.to_string(),
+                        mask: number(attribute(&item, "mask")?)?,
+                        message: message(&item)?,
+                    });
+                }
+            }
+            "templates" => {
+                for item in items {
+                    provider.templates.push(parse_template(&item)?);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for list in node.children().filter(|node| is(node, "events")) {
+        for item in list.children().filter(Node::is_element) {
+            let event = parse_event(&item, &mut provider)?;
+            provider.events.push(event);
+        }
+    }
+
+    Ok(provider)
+}
+
+fn parse_opcode(node: &Node, task: u16) -> Result<Opcode, ResError> {
+    Ok(Opcode {
+        name: attribute(node, "name")?.to_string(),
+        value: number(attribute(node, "value")?)?,
+        task,
+        message: message(node)?,
+        winmeta: None,
+    })
+}
+
+fn parse_template(node: &Node) -> Result<Template, ResError> {
+    let mut items = Vec::new();
+    for data in node.children().filter(|node| is(node, "data")) {
+        let in_type = attribute(&data, "inType")?;
+        let in_type = lookup(IN_TYPES, in_type)
+            .ok_or_else(|| error(&format!("unsupported inType \"{in_type}\"")))?;
+        let out_type = match data.attribute("outType") {
+            Some(out_type) => lookup(OUT_TYPES, out_type)
+                .ok_or_else(|| error(&format!("unsupported outType \"{out_type}\"")))?,
+            None => default_out_type(in_type),
+        };
+
+        items.push(TemplateItem {
+            name: attribute(&data, "name")?.to_string(),
+            in_type,
+            out_type,
+            count: data
+                .attribute("count")
+                .map(number)
+                .transpose()?
+                .unwrap_or(1),
+            length: data
+                .attribute("length")
+                .map(number)
+                .transpose()?
+                .unwrap_or(0),
+        });
+    }
+
+    Ok(Template {
+        tid: attribute(node, "tid")?.to_string(),
+        items,
+    })
+}
+
+fn parse_event(node: &Node, provider: &mut Provider) -> Result<Event, ResError> {
+    let mut event = Event {
+        value: number(attribute(node, "value")?)?,
+        version: node
+            .attribute("version")
+            .map(number)
+            .transpose()?
+            .unwrap_or(0),
+        channel: 0,
+        level: 0,
+        opcode: 0,
+        task: 0,
+        keywords: 0,
+        message: message(node)?,
+        template: None,
+        level_def: None,
+        opcode_def: None,
+        task_def: None,
+    };
+
+    if let Some(channel) = node.attribute("channel") {
+        event.channel = provider
+            .channels
+            .iter()
+            .find(|c| c.chid == channel || c.name == channel)
+            .map(|c| c.value)
+            .ok_or_else(|| error(&format!("unknown channel \"{channel}\"")))?;
+    }
+
+    if let Some(level) = node.attribute("level") {
+        let index = match provider.levels.iter().position(|l| l.name == level) {
+            Some(index) => index,
+            None => {
+                let value = lookup(LEVELS, level)
+                    .ok_or_else(|| error(&format!("unknown level \"{level}\"")))?;
+                provider.levels.push(Level {
+                    name: level.to_string(),
+                    value,
+                    message: None,
+                    winmeta: Some(0x5000_0000 | u32::from(value)),
+                });
+                provider.levels.len() - 1
+            }
+        };
+        event.level = provider.levels[index].value;
+        event.level_def = Some(index);
+    }
+
+    if let Some(task) = node.attribute("task") {
+        let index = provider
+            .tasks
+            .iter()
+            .position(|t| t.name == task)
+            .ok_or_else(|| error(&format!("unknown task \"{task}\"")))?;
+        event.task = provider.tasks[index].value;
+        event.task_def = Some(index);
+    }
+
+    if let Some(opcode) = node.attribute("opcode") {
+        // Opcodes of the event's task take precedence over the provider-wide ones
+        let found = provider
+            .opcodes
+            .iter()
+            .position(|o| o.name == opcode && o.task == event.task)
+            .or_else(|| {
+                provider
+                    .opcodes
+                    .iter()
+                    .position(|o| o.name == opcode && o.task == 0)
+            });
+        let index = match found {
+            Some(index) => index,
+            None => {
+                let value = lookup(OPCODES, opcode)
+                    .ok_or_else(|| error(&format!("unknown opcode \"{opcode}\"")))?;
+                provider.opcodes.push(Opcode {
+                    name: opcode.to_string(),
+                    value,
+                    task: 0,
+                    message: None,
+                    winmeta: Some(0x3000_0000 | u32::from(value)),
+                });
+                provider.opcodes.len() - 1
+            }
+        };
+        event.opcode = provider.opcodes[index].value;
+        event.opcode_def = Some(index);
+    }
+
+    if let Some(keywords) = node.attribute("keywords") {
+        for keyword in keywords.split_whitespace() {
+            event.keywords |= provider
+                .keywords
+                .iter()
+                .find(|k| k.name == keyword)
+                .map(|k| k.mask)
+                .ok_or_else(|| error(&format!("unknown keyword \"{keyword}\"")))?;
+        }
+    }
+
+    if let Some(template) = node.attribute("template") {
+        event.template = Some(
+            provider
+                .templates
+                .iter()
+                .position(|t| t.tid == template)
+                .ok_or_else(|| error(&format!("unknown template \"{template}\"")))?,
+        );
+    }
+
+    Ok(event)
+}
+
+/// Output type used when the `outType` attribute is not set.
+fn default_out_type(in_type: u8) -> u8 {
+    match in_type {
+        16 => 21,     // win:Pointer -> win:HexInt64
+        17 | 18 => 2, // win:FILETIME, win:SYSTEMTIME -> xs:dateTime
+        19..=21 => in_type,
+        _ => in_type.min(15),
+    }
+}
+
+fn is(node: &Node, name: &str) -> bool {
+    node.is_element() && node.tag_name().name() == name
+}
+
+fn attribute<'a>(node: &Node<'a, '_>, name: &str) -> Result<&'a str, ResError> {
+    node.attribute(name).ok_or_else(|| {
+        error(&format!(
+            "<{}> is missing the \"{name}\" attribute",
+            node.tag_name().name()
+        ))
+    })
+}
+
+/// Value of a `<string>` in the string table.
+fn value(node: &Node) -> Result<String, ResError> {
+    Ok(attribute(node, "value")?.to_string())
+}
+
+/// Id of the string referenced by the `message` attribute. (`$(string.Id)`)
+fn message(node: &Node) -> Result<Option<String>, ResError> {
+    node.attribute("message")
+        .map(|message| {
+            message
+                .strip_prefix("$(string.")
+                .and_then(|id| id.strip_suffix(')'))
+                .map(str::to_string)
+                .ok_or_else(|| error(&format!("invalid message reference \"{message}\"")))
+        })
+        .transpose()
+}
+
+fn lookup(list: &[(&str, u8)], name: &str) -> Option<u8> {
+    list.iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, value)| *value)
+}
+
+fn number<T: TryFrom<u64>>(value: &str) -> Result<T, ResError> {
+    let value = value.trim();
+    match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => value.parse(),
+    }
+    .ok()
+    .and_then(|number| T::try_from(number).ok())
+    .ok_or_else(|| error(&format!("invalid number \"{value}\"")))
+}
+
+/// Parses a GUID like `{01234567-89AB-CDEF-0123-456789ABCDEF}` into its binary layout.
+fn parse_guid(value: &str) -> Result<[u8; 16], ResError> {
+    let invalid = || error(&format!("invalid GUID \"{value}\""));
+    let hex = value.trim_start_matches('{').trim_end_matches('}');
+    let parts: Vec<&str> = hex.split('-').collect();
+    let lengths: Vec<usize> = parts.iter().map(|part| part.len()).collect();
+    if lengths != [8, 4, 4, 4, 12] {
+        return Err(invalid());
+    }
+
+    let data1 = u32::from_str_radix(parts[0], 16).map_err(|_| invalid())?;
+    let data2 = u16::from_str_radix(parts[1], 16).map_err(|_| invalid())?;
+    let data3 = u16::from_str_radix(parts[2], 16).map_err(|_| invalid())?;
+    let data4 =
+        u64::from_str_radix(&format!("{}{}", parts[3], parts[4]), 16).map_err(|_| invalid())?;
+
+    let mut guid = [0; 16];
+    guid[..4].copy_from_slice(&data1.to_le_bytes());
+    guid[4..6].copy_from_slice(&data2.to_le_bytes());
+    guid[6..8].copy_from_slice(&data3.to_le_bytes());
+    guid[8..].copy_from_slice(&data4.to_be_bytes());
+    Ok(guid)
+}
+
+/// Windows Language Code of common cultures.
+fn lcid(culture: &str) -> Option<u16> {
+    const CULTURES: &[(&str, u16)] = &[
+        ("ar-SA", 0x0401),
+        ("bg-BG", 0x0402),
+        ("ca-ES", 0x0403),
+        ("zh-TW", 0x0404),
+        ("cs-CZ", 0x0405),
+        ("da-DK", 0x0406),
+        ("de-DE", 0x0407),
+        ("el-GR", 0x0408),
+        ("en-US", 0x0409),
+        ("fi-FI", 0x040B),
+        ("fr-FR", 0x040C),
+        ("he-IL", 0x040D),
+        ("hu-HU", 0x040E),
+        ("it-IT", 0x0410),
+        ("ja-JP", 0x0411),
+        ("ko-KR", 0x0412),
+        ("nl-NL", 0x0413),
+        ("nb-NO", 0x0414),
+        ("pl-PL", 0x0415),
+        ("pt-BR", 0x0416),
+        ("ro-RO", 0x0418),
+        ("ru-RU", 0x0419),
+        ("hr-HR", 0x041A),
+        ("sk-SK", 0x041B),
+        ("sv-SE", 0x041D),
+        ("th-TH", 0x041E),
+        ("tr-TR", 0x041F),
+        ("uk-UA", 0x0422),
+        ("sl-SI", 0x0424),
+        ("et-EE", 0x0425),
+        ("lv-LV", 0x0426),
+        ("lt-LT", 0x0427),
+        ("vi-VN", 0x042A),
+        ("hi-IN", 0x0439),
+        ("zh-CN", 0x0804),
+        ("de-CH", 0x0807),
+        ("en-GB", 0x0809),
+        ("es-MX", 0x080A),
+        ("pt-PT", 0x0816),
+        ("sr-Latn-RS", 0x241A),
+        ("de-AT", 0x0C07),
+        ("en-AU", 0x0C09),
+        ("es-ES", 0x0C0A),
+        ("fr-CA", 0x0C0C),
+        ("en-CA", 0x1009),
+        ("zh-HK", 0x0C04),
+    ];
+
+    CULTURES
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(culture))
+        .map(|(_, lcid)| *lcid)
+}
+
+fn error(message: &str) -> ResError {
+    ResError::Parse(format!("instrumentation manifest: {message}"))
+}
@@ -0,0 +1,393 @@
+//! Font resource. ([`FONT`](crate::font::Font))
+
+use crate::custom::ResType;
+use crate::util::{self, push_u16, push_u32, read_u16, read_u16_be, read_u32, read_u32_be};
+use crate::{ResError, ResWriter, Resource};
+use std::path::{Path, PathBuf};
+
+/// Font resource. (`FONT` with its `FONTDIR`)
+///
+/// Embeds TrueType or OpenType fonts (`.ttf`, `.otf`, `.ttc`) and raster fonts (`.fnt`, `.fon`).
+/// The fonts are validated and their family names are read to build the font directory.
+/// Font data can be loaded at runtime with `FindResourceW` and `AddFontMemResourceEx`.
+///
+/// Only one font directory can be embedded, so all fonts need to be added to the same resource.
+/// Raster fonts of a `.fon` file are embedded as separate fonts with consecutive ids.
+///
+/// ### Example:
+/// ```
+/// # use winscribe::font::Font;
+/// Font::new(1, "fonts/Brand-Regular.ttf").with(2, "fonts/Brand-Bold.otf");
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/font-resource>
+#[derive(Debug, Clone)]
+pub struct Font {
+    /// Font files with the id of their resource.
+    pub fonts: Vec<(u16, PathBuf)>,
+    /// Windows Language Code of the resource. Uses the builder's default when not set.
+    pub language: Option<u16>,
+}
+
+impl Font {
+    /// Creates a new font resource.
+    ///
+    /// * `id`: A unique 16-bit unsigned integer.
+    /// * `path`: Path to the font file.
+    pub fn new<P: Into<PathBuf>>(id: u16, path: P) -> Self {
+        Self {
+            fonts: vec![(id, path.into())],
+            language: None,
+        }
+    }
+
+    /// Adds another font file.
+    pub fn with<P: Into<PathBuf>>(mut self, id: u16, path: P) -> Self {
+        self.fonts.push((id, path.into()));
+        self
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+}
+
+impl Resource for Font {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        let mut entries: Vec<(u16, Vec<u8>)> = Vec::new();
+        let mut lines = Vec::new();
+
+        for (id, path) in &self.fonts {
+            let data = std::fs::read(path)?;
+            match FontKind::detect(&data) {
+                Some(FontKind::OpenType) => {
+                    entries.push((*id, opentype_entry(&data).map_err(|e| error(path, e))?));
+                    lines.push((*id, util::escape_path(path)?));
+                }
+                Some(FontKind::Raster) => {
+                    entries.push((*id, raster_entry(&data).map_err(|e| error(path, e))?));
+                    lines.push((*id, util::escape_path(path)?));
+                }
+                Some(FontKind::Executable) => {
+                    let fonts = extract_fon(&data).map_err(|e| error(path, e))?;
+                    for (index, font) in fonts.iter().enumerate() {
+                        let id = u16::try_from(index)
+                            .ok()
+                            .and_then(|index| id.checked_add(index))
+                            .ok_or(ResError::Custom("Font id is out of range!"))?;
+                        entries.push((id, raster_entry(font).map_err(|e| error(path, e))?));
+                        let font = util::hashed_out_file("font", "fnt", font)?;
+                        lines.push((id, util::escape_path(font)?));
+                    }
+                }
+                None => return Err(error(path, "unsupported font format")),
+            }
+        }
+
+        let mut ids: Vec<u16> = entries.iter().map(|(id, _)| *id).collect();
+        ids.sort_unstable();
+        if ids.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(ResError::Custom("Font resource contains duplicate ids!"));
+        }
+
+        let mut directory = Vec::new();
+        push_u16(&mut directory, entries.len() as u16);
+        for (id, entry) in entries {
+            push_u16(&mut directory, id);
+            directory.extend(entry);
+        }
+        let directory = util::hashed_out_file("fontdir", "bin", &directory)?;
+
+        for (id, path) in lines {
+            writer.define(ResType::FONT, id, self.language)?;
+            writer.line(format!("{id} {} \"{path}\"", ResType::FONT));
+        }
+        writer.define(ResType::FONTDIR, "FONTDIR", self.language)?;
+        writer.line(format!(
+            "FONTDIR {} \"{}\"",
+            ResType::FONTDIR,
+            util::escape_path(directory)?
+        ));
+        Ok(())
+    }
+}
+
+enum FontKind {
+    /// TrueType or OpenType font or collection.
+    OpenType,
+    /// Windows raster font. (`.fnt`)
+    Raster,
+    /// Font resource file containing raster fonts. (`.fon`)
+    Executable,
+}
+
+impl FontKind {
+    fn detect(data: &[u8]) -> Option<Self> {
+        match data.get(..4)? {
+            [0x00, 0x01, 0x00, 0x00] | b"OTTO" | b"true" | b"ttcf" => Some(Self::OpenType),
+            [b'M', b'Z', ..] => Some(Self::Executable),
+            [0x00, 0x02 | 0x03, ..] => Some(Self::Raster),
+            _ => None,
+        }
+    }
+}
+
+/// Size of the fixed fields of a `FONTDIRENTRY`, which match the start of the `.fnt` header.
+const DIR_ENTRY_SIZE: usize = 113;
+
+/// Builds the `FONTDIRENTRY` of a raster font from its header.
+fn raster_entry(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    if data.len() < 118 {
+        return Err("raster font header is truncated");
+    }
+    let size = read_u32(data, 2).ok_or("invalid raster font header")?;
+    if size as usize > data.len() {
+        return Err("raster font is truncated");
+    }
+
+    let face = read_u32(data, 105).ok_or("invalid raster font header")? as usize;
+    let face = data
+        .get(face..)
+        .and_then(|face| face.split(|byte| *byte == 0).next())
+        .filter(|face| !face.is_empty())
+        .ok_or("raster font has no face name")?;
+
+    let mut entry = data[..DIR_ENTRY_SIZE].to_vec();
+    entry.push(0); // Device name
+    entry.extend(face);
+    entry.push(0);
+    Ok(entry)
+}
+
+/// Synthesizes the `FONTDIRENTRY` of a TrueType or OpenType font.
+fn opentype_entry(data: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let font = OpenType::parse(data)?;
+    let family = font.name(1).ok_or("font has no family name")?;
+    let copyright = font.name(0).unwrap_or_default();
+    let (weight, italic) = font.style();
+
+    let mut entry = Vec::with_capacity(DIR_ENTRY_SIZE + family.len() + 2);
+    push_u16(&mut entry, 0x0300); // dfVersion
+    push_u32(&mut entry, data.len() as u32); // dfSize
+    let mut copyright = ansi(&copyright);
+    copyright.truncate(59);
+    copyright.resize(60, 0);
+    entry.extend(copyright); // dfCopyright
+    entry.resize(80, 0); // dfType, dfPoints, dfVertRes, dfHorizRes, dfAscent, dfLeading
+    entry.push(u8::from(italic)); // dfItalic
+    entry.extend([0, 0]); // dfUnderline, dfStrikeOut
+    push_u16(&mut entry, weight); // dfWeight
+    entry.push(1); // dfCharSet: DEFAULT_CHARSET
+    entry.resize(90, 0); // dfPixWidth, dfPixHeight
+    entry.push(0x06); // dfPitchAndFamily: TMPF_VECTOR | TMPF_TRUETYPE
+    entry.resize(DIR_ENTRY_SIZE, 0);
+    entry.push(0); // Device name
+    entry.extend(ansi(&family));
+    entry.push(0);
+    Ok(entry)
+}
+
+/// Converts to a null-free single-byte string, replacing characters outside of Latin-1.
+fn ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .filter(|c| *c != '\0')
+        .map(|c| u8::try_from(c).unwrap_or(b'?'))
+        .collect()
+}
+
+/// Table directory of a TrueType or OpenType font.
+struct OpenType<'a> {
+    data: &'a [u8],
+    /// Offset of the table directory.
+    offset: usize,
+}
+
+impl<'a> OpenType<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, &'static str> {
+        // Collections are validated by their first font
+        let offset = if data.starts_with(b"ttcf") {
+            read_u32_be(data, 12).ok_or("font collection header is truncated")? as usize
+        } else {
+            0
+        };
+
+        let tables = read_u16_be(data, offset + 4).ok_or("font header is truncated")?;
+        let end = offset + 12 + usize::from(tables) * 16;
+        if end > data.len() {
+            return Err("font table directory is truncated");
+        }
+
+        let font = Self { data, offset };
+        if font.table(b"name").is_none() {
+            return Err("font has no naming table");
+        }
+        Ok(font)
+    }
+
+    fn table(&self, tag: &[u8; 4]) -> Option<&'a [u8]> {
+        let tables = usize::from(read_u16_be(self.data, self.offset + 4)?);
+        (0..tables)
+            .map(|index| self.offset + 12 + index * 16)
+            .find(|record| self.data.get(*record..record + 4) == Some(tag))
+            .and_then(|record| {
+                let offset = read_u32_be(self.data, record + 8)? as usize;
+                let length = read_u32_be(self.data, record + 12)? as usize;
+                self.data.get(offset..offset.checked_add(length)?)
+            })
+    }
+
+    /// Reads a name from the naming table, preferring the English Windows name.
+    fn name(&self, name_id: u16) -> Option<String> {
+        let table = self.table(b"name")?;
+        let count = usize::from(read_u16_be(table, 2)?);
+        let storage = usize::from(read_u16_be(table, 4)?);
+
+        let mut fallback = None;
+        for index in 0..count {
+            let record = 6 + index * 12;
+            let platform = read_u16_be(table, record)?;
+            let encoding = read_u16_be(table, record + 2)?;
+            let language = read_u16_be(table, record + 4)?;
+            if read_u16_be(table, record + 6)? != name_id {
+                continue;
+            }
+
+            let length = usize::from(read_u16_be(table, record + 8)?);
+            let offset = storage + usize::from(read_u16_be(table, record + 10)?);
+            let Some(bytes) = table.get(offset..offset + length) else {
+                continue;
+            };
+
+            let name = match (platform, encoding) {
+                // Unicode or Windows: UTF-16BE
+                (0, _) | (3, 0 | 1 | 10) => String::from_utf16(
+                    &bytes
+                        .chunks_exact(2)
+                        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                        .collect::<Vec<_>>(),
+                )
+                .ok(),
+                // Macintosh Roman: treated as Latin-1
+                (1, 0) => Some(bytes.iter().map(|byte| char::from(*byte)).collect()),
+                _ => None,
+            };
+
+            match name {
+                Some(name) if platform == 3 && language == 0x0409 => return Some(name),
+                Some(name) if fallback.is_none() => fallback = Some(name),
+                _ => {}
+            }
+        }
+
+        fallback.filter(|name| !name.is_empty())
+    }
+
+    /// Weight and italic flag from the `OS/2` table.
+    fn style(&self) -> (u16, bool) {
+        self.table(b"OS/2")
+            .and_then(|table| {
+                let weight = read_u16_be(table, 4)?;
+                let selection = read_u16_be(table, 62)?;
+                Some((weight, selection & 0x1 != 0))
+            })
+            .unwrap_or((400, false))
+    }
+}
+
+/// Extracts the raster fonts from the resources of a `.fon` (NE executable) file.
+fn extract_fon(data: &[u8]) -> Result<Vec<&[u8]>, &'static str> {
+    let header = read_u32(data, 0x3C).ok_or("executable header is truncated")? as usize;
+    if data.get(header..header + 2) != Some(b"NE") {
+        return Err("not a 16-bit font resource file");
+    }
+
+    let mut offset = header
+        + usize::from(read_u16(data, header + 0x24).ok_or("executable header is truncated")?);
+    let shift = read_u16(data, offset).ok_or("resource table is truncated")?;
+    if shift >= 16 {
+        return Err("invalid resource table alignment");
+    }
+    offset += 2;
+
+    let mut fonts = Vec::new();
+    loop {
+        let type_id = read_u16(data, offset).ok_or("resource table is truncated")?;
+        if type_id == 0 {
+            break;
+        }
+        let count = usize::from(read_u16(data, offset + 2).ok_or("resource table is truncated")?);
+        offset += 8;
+
+        for index in 0..count {
+            let entry = offset + index * 12;
+            let start =
+                usize::from(read_u16(data, entry).ok_or("resource table is truncated")?) << shift;
+            let length =
+                usize::from(read_u16(data, entry + 2).ok_or("resource table is truncated")?)
+                    << shift;
+
+            // Integer type id of RT_FONT
+            if type_id & 0x8000 != 0 && ResType::Int(type_id & 0x7FFF) == ResType::FONT {
+                let font = data
+                    .get(start..start + length)
+                    .ok_or("font resource is out of bounds")?;
+                let size = read_u32(font, 2).ok_or("raster font header is truncated")? as usize;
+                fonts.push(font.get(..size).ok_or("raster font is truncated")?);
+            }
+        }
+        offset += count * 12;
+    }
+
+    if fonts.is_empty() {
+        return Err("no fonts found in the font resource file");
+    }
+    Ok(fonts)
+}
+
+fn error(path: &Path, message: &str) -> ResError {
+    ResError::Parse(format!("font file \"{}\": {message}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Minimal font with only a naming table containing the family name.
+    fn font(family: &str) -> Vec<u8> {
+        let name: Vec<u8> = family.encode_utf16().flat_map(u16::to_be_bytes).collect();
+
+        let mut table = Vec::new();
+        table.extend([0, 0, 0, 1, 0, 18]); // Format, count, storage offset
+        table.extend([0, 3, 0, 1, 0x04, 0x09, 0, 1]); // Windows, Unicode BMP, en-US, family
+        table.extend((name.len() as u16).to_be_bytes());
+        table.extend([0, 0]);
+        table.extend(name);
+
+        let mut data = Vec::new();
+        data.extend([0, 1, 0, 0, 0, 1, 0, 16, 0, 0, 0, 0]); // Version, 1 table
+        data.extend(b"name");
+        data.extend([0; 4]); // Checksum
+        data.extend(28u32.to_be_bytes());
+        data.extend((table.len() as u32).to_be_bytes());
+        data.extend(table);
+        data
+    }
+
+    #[test]
+    fn opentype() {
+        let data = font("Brand Sans");
+        assert!(matches!(FontKind::detect(&data), Some(FontKind::OpenType)));
+        assert_eq!(
+            OpenType::parse(&data).unwrap().name(1).as_deref(),
+            Some("Brand Sans")
+        );
+
+        let entry = opentype_entry(&data).unwrap();
+        assert_eq!(entry.len(), DIR_ENTRY_SIZE + 12);
+        assert_eq!(&entry[DIR_ENTRY_SIZE + 1..], b"Brand Sans\0");
+
+        assert!(OpenType::parse(&data[..20]).is_err());
+    }
+}
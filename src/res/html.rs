@@ -0,0 +1,221 @@
+//! HTML resource. ([`HTML`](crate::html::Html))
+
+use crate::custom::ResType;
+use crate::{ResError, ResId, ResWriter, Resource, util};
+use std::path::{Path, PathBuf};
+
+/// HTML resource. (`HTML`)
+///
+/// Embeds an HTML page with the assets it depends on (stylesheets, scripts, images).
+/// Each of them is a separate resource named after the file, which can be loaded with
+/// `res://` protocol URLs, like `res://app.exe/style.css`.
+///
+/// Relative `src`, `href` and CSS `url()` references of the page and its stylesheets are
+/// checked, and a cargo warning is printed for each one which is not embedded.
+///
+/// ### Example:
+/// ```
+/// # use winscribe::html::Html;
+/// Html::new("about.html", "res/about.html")
+///     .with_asset("style.css", "res/style.css")
+///     .with_asset("logo.png", "res/logo.png");
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/html-resource>
+#[derive(Debug, Clone)]
+pub struct Html {
+    id: ResId,
+    language: Option<u16>,
+    path: PathBuf,
+    assets: Vec<(String, PathBuf)>,
+}
+
+impl Html {
+    /// Creates a new HTML resource.
+    ///
+    /// * `id`: A unique name (ex: `about.html`) or a 16-bit unsigned integer.
+    /// * `path`: Path to the HTML file.
+    pub fn new<N, P>(id: N, path: P) -> Self
+    where
+        N: Into<ResId>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            id: id.into(),
+            language: None,
+            path: path.into(),
+            assets: Vec::new(),
+        }
+    }
+
+    /// Embeds an asset used by the page.
+    ///
+    /// * `name`: Name the page references the asset by. (ex: `style.css`)
+    /// * `path`: Path to the asset file.
+    pub fn with_asset<N, P>(mut self, name: N, path: P) -> Self
+    where
+        N: Into<String>,
+        P: Into<PathBuf>,
+    {
+        self.assets.push((name.into(), path.into()));
+        self
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Prints a warning for each relative reference which is not embedded.
+    fn check_references(&self) -> Result<(), ResError> {
+        let mut documents = vec![self.path.as_path()];
+        documents.extend(
+            self.assets
+                .iter()
+                .map(|(_, path)| path.as_path())
+                .filter(|path| is_document(path)),
+        );
+
+        for document in documents {
+            let content = String::from_utf8_lossy(&std::fs::read(document)?).into_owned();
+            for reference in references(&content) {
+                let embedded = self.id.to_string().eq_ignore_ascii_case(reference)
+                    || self
+                        .assets
+                        .iter()
+                        .any(|(name, _)| name.eq_ignore_ascii_case(reference));
+                if !embedded {
+                    util::warning(format!(
+                        "\"{}\" references \"{reference}\" which is not embedded as an HTML resource",
+                        document.display()
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Resource for Html {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        if let ResId::Name(name) = &self.id {
+            validate_asset_name(name)?;
+        }
+        for (name, _) in &self.assets {
+            validate_asset_name(name)?;
+        }
+        self.check_references()?;
+
+        writer.register(ResType::HTML, self.id.clone(), self.language)?;
+        writer.line(format!(
+            "{} HTML \"{}\"",
+            statement_name(&self.id),
+            util::escape_path(&self.path)?
+        ));
+        for (name, path) in &self.assets {
+            writer.register(ResType::HTML, name, self.language)?;
+            writer.line(format!(
+                "{} HTML \"{}\"",
+                statement_name(&ResId::from(name)),
+                util::escape_path(path)?
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Asset names are part of the `res://` URL, so they can't contain path separators.
+fn validate_asset_name(name: &str) -> Result<(), ResError> {
+    let valid = !name.is_empty()
+        && !name.starts_with(|c: char| c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ResError::InvalidIdentifier(name.to_string()))
+    }
+}
+
+/// Id as written in the resource statement. Names are quoted, as the resource compiler
+/// would split them at `-` and `.` characters.
+fn statement_name(id: &ResId) -> String {
+    match id {
+        ResId::Int(_) => id.to_string(),
+        ResId::Name(name) => format!("\"{name}\""),
+    }
+}
+
+/// Files which can reference other assets.
+fn is_document(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "htm" | "html" | "css"))
+}
+
+/// Relative references of the `src`, `href` attributes and CSS `url()` values.
+fn references(content: &str) -> Vec<&str> {
+    let lower = content.to_ascii_lowercase();
+    let mut references = Vec::new();
+
+    for pattern in ["src=", "href=", "url("] {
+        for (start, _) in lower.match_indices(pattern) {
+            let value = content[start + pattern.len()..].trim_start();
+            let value = match value.chars().next() {
+                Some(quote @ ('"' | '\'')) => value[1..].split(quote).next(),
+                _ => value
+                    .split(|c: char| c.is_whitespace() || matches!(c, '>' | ')'))
+                    .next(),
+            };
+
+            // Drop the query and fragment
+            let Some(value) = value.and_then(|value| value.split(['?', '#']).next()) else {
+                continue;
+            };
+            let value = value.trim_start_matches("./");
+
+            let relative = !value.is_empty() && !value.starts_with('/') && !value.contains(':');
+            if relative && !references.contains(&value) {
+                references.push(value);
+            }
+        }
+    }
+
+    references
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relative_references() {
+        let html = r##"<html><head>
+<link rel="stylesheet" HREF="style.css?v=2">
+<script src='./app.js'></script>
+<style>body { background: url(bg.png); }</style>
+</head><body>
+<a href="#top">Top</a> <a href="https://example.com">Site</a>
+<img src="res://shell32.dll/2/130"> <img src=logo.png alt="">
+<a href="/root.html">Root</a> <a href="mailto:me@example.com">Mail</a>
+</body></html>"##;
+
+        assert_eq!(
+            references(html),
+            ["app.js", "logo.png", "style.css", "bg.png"]
+        );
+    }
+
+    #[test]
+    fn quoted_names() {
+        assert_eq!(
+            statement_name(&ResId::from("app-v2.min.js")),
+            "\"app-v2.min.js\""
+        );
+        assert_eq!(statement_name(&ResId::from(7)), "7");
+    }
+}
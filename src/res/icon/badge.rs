@@ -0,0 +1,317 @@
+use crate::ResError;
+use crate::image::Image;
+use crate::version_info::FileFlags;
+
+/// A mark drawn onto every frame of an icon, to tell apart builds at a glance.
+///
+/// ### Example:
+/// ```no_run
+/// # use winscribe::icon::{Badge, BadgeStyle, Icon};
+/// # fn main() -> Result<(), winscribe::ResError> {
+/// // "DEV" banner on debug builds, "BETA" on prerelease versions
+/// Icon::app("assets/application.ico").with_badge(Badge::from_env(BadgeStyle::Banner)?);
+/// // Always a green corner
+/// Icon::app("assets/application.ico").with_badge(Badge::new(BadgeStyle::Corner, "", [0, 160, 0]));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Badge {
+    /// Shape of the badge.
+    pub style: BadgeStyle,
+    /// Text of the banner. Only uppercase letters, digits, `-` and `.` are drawn.
+    pub label: String,
+    /// Color of the badge as RGB.
+    pub color: [u8; 3],
+}
+
+/// Shape of a [`Badge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BadgeStyle {
+    /// Triangle in the bottom-right corner.
+    Corner,
+    /// Bar across the bottom with the label on frames of 32 pixels or larger.
+    Banner,
+}
+
+impl Badge {
+    /// Creates a new badge.
+    pub fn new<T: Into<String>>(style: BadgeStyle, label: T, color: [u8; 3]) -> Self {
+        Self {
+            style,
+            label: label.into(),
+            color,
+        }
+    }
+
+    /// Creates the badge of the build based on [`FileFlags::from_env`].
+    ///
+    /// Returns an orange `DEV` badge for debug builds, a blue `BETA` badge for prerelease
+    /// versions and `None` for release builds.
+    pub fn from_env(style: BadgeStyle) -> Result<Option<Self>, ResError> {
+        let flags = FileFlags::from_env()?.val();
+        let badge = if flags & FileFlags::Debug.val() != 0 {
+            Some(Self::new(style, "DEV", [0xE6, 0x7E, 0x22]))
+        } else if flags & FileFlags::Prerelease.val() != 0 {
+            Some(Self::new(style, "BETA", [0x29, 0x80, 0xB9]))
+        } else {
+            None
+        };
+        Ok(badge)
+    }
+
+    /// Draws the badge onto the image.
+    pub(super) fn draw(&self, image: &mut Image) {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let [r, g, b] = self.color;
+
+        match self.style {
+            BadgeStyle::Corner => {
+                let leg = width.min(height) / 2;
+                for y in height - leg..height {
+                    for x in width - leg..width {
+                        if (x - (width - leg)) + (y - (height - leg)) + 1 >= leg {
+                            set_pixel(image, x, y, [r, g, b, 0xFF]);
+                        }
+                    }
+                }
+            }
+            BadgeStyle::Banner => {
+                let bar = (height * 3 / 10).max(2).min(height);
+                for y in height - bar..height {
+                    for x in 0..width {
+                        set_pixel(image, x, y, [r, g, b, 0xFF]);
+                    }
+                }
+                self.draw_label(image, bar);
+            }
+        }
+    }
+
+    /// Draws the label centered in the banner, if it fits.
+    fn draw_label(&self, image: &mut Image, bar: usize) {
+        let width = image.width as usize;
+        let height = image.height as usize;
+        let glyphs: Vec<[u8; 7]> = self.label.chars().map(glyph).collect();
+        if glyphs.is_empty() {
+            return;
+        }
+
+        // Glyphs are 5x7 pixels with 1 pixel spacing, scaled up as much as possible
+        let columns = glyphs.len() * 6 - 1;
+        let scale = (bar.saturating_sub(2) / 7).min(width.saturating_sub(2) / columns);
+        if scale == 0 {
+            return;
+        }
+
+        let left = (width - columns * scale) / 2;
+        let top = height - bar + (bar - 7 * scale) / 2;
+        for (index, rows) in glyphs.iter().enumerate() {
+            for (row, bits) in rows.iter().enumerate() {
+                for column in 0..5 {
+                    if bits & (0b10000 >> column) == 0 {
+                        continue;
+                    }
+                    let x = left + (index * 6 + column) * scale;
+                    let y = top + row * scale;
+                    for dy in 0..scale {
+                        for dx in 0..scale {
+                            set_pixel(image, x + dx, y + dy, [0xFF; 4]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn set_pixel(image: &mut Image, x: usize, y: usize, color: [u8; 4]) {
+    let offset = (y * image.width as usize + x) * 4;
+    image.pixels[offset..offset + 4].copy_from_slice(&color);
+}
+
+/// Rows of the 5x7 pixel glyph of the character, the most significant bit on the left.
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        'A' => [
+            0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+        ],
+        'B' => [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110,
+        ],
+        'C' => [
+            0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110,
+        ],
+        'D' => [
+            0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110,
+        ],
+        'E' => [
+            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111,
+        ],
+        'F' => [
+            0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000,
+        ],
+        'G' => [
+            0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111,
+        ],
+        'H' => [
+            0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+        ],
+        'I' => [
+            0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        'J' => [
+            0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100,
+        ],
+        'K' => [
+            0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001,
+        ],
+        'L' => [
+            0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111,
+        ],
+        'M' => [
+            0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001,
+        ],
+        'N' => [
+            0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001,
+        ],
+        'O' => [
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+        'P' => [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000,
+        ],
+        'Q' => [
+            0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101,
+        ],
+        'R' => [
+            0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001,
+        ],
+        'S' => [
+            0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110,
+        ],
+        'T' => [
+            0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100,
+        ],
+        'U' => [
+            0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+        ],
+        'V' => [
+            0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100,
+        ],
+        'W' => [
+            0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010,
+        ],
+        'X' => [
+            0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001,
+        ],
+        'Y' => [
+            0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100,
+        ],
+        'Z' => [
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111,
+        ],
+        '0' => [
+            0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110,
+        ],
+        '1' => [
+            0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+        ],
+        '2' => [
+            0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111,
+        ],
+        '3' => [
+            0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110,
+        ],
+        '4' => [
+            0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010,
+        ],
+        '5' => [
+            0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110,
+        ],
+        '6' => [
+            0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110,
+        ],
+        '7' => [
+            0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000,
+        ],
+        '8' => [
+            0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+        ],
+        '9' => [
+            0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100,
+        ],
+        '-' => [
+            0b00000, 0b00000, 0b00000, 0b11111, 0b00000, 0b00000, 0b00000,
+        ],
+        '.' => [
+            0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b01100, 0b01100,
+        ],
+        _ => [0; 7],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transparent(size: u32) -> Image {
+        Image {
+            width: size,
+            height: size,
+            pixels: vec![0; (size * size * 4) as usize],
+        }
+    }
+
+    fn pixel(image: &Image, x: usize, y: usize) -> Vec<u8> {
+        let offset = (y * image.width as usize + x) * 4;
+        image.pixels[offset..offset + 4].to_vec()
+    }
+
+    #[test]
+    fn banner() {
+        let badge = Badge::new(BadgeStyle::Banner, "dev", [255, 0, 0]);
+
+        let mut image = transparent(32);
+        badge.draw(&mut image);
+        assert_eq!(pixel(&image, 0, 0), [0, 0, 0, 0]);
+        assert_eq!(pixel(&image, 0, 31), [255, 0, 0, 255]);
+        // Top-left of the "D", centered in the 9 pixel tall bar
+        assert_eq!(pixel(&image, 7, 24), [255; 4]);
+
+        // No room for the label
+        let mut image = transparent(16);
+        badge.draw(&mut image);
+        assert!(image.pixels.chunks_exact(4).all(|p| p != [255; 4]));
+
+        // Fully covered
+        let mut image = transparent(1);
+        badge.draw(&mut image);
+        assert_eq!(image.pixels, [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn corner() {
+        let badge = Badge::new(BadgeStyle::Corner, "", [0, 255, 0]);
+
+        let mut image = transparent(16);
+        badge.draw(&mut image);
+        // Triangle with 8 pixel legs, its hypotenuse from (15, 8) to (8, 15)
+        assert_eq!(pixel(&image, 15, 15), [0, 255, 0, 255]);
+        assert_eq!(pixel(&image, 15, 8), [0, 255, 0, 255]);
+        assert_eq!(pixel(&image, 8, 15), [0, 255, 0, 255]);
+        assert_eq!(pixel(&image, 14, 8), [0; 4]);
+        assert_eq!(pixel(&image, 8, 14), [0; 4]);
+        assert_eq!(pixel(&image, 7, 15), [0; 4]);
+        assert_eq!(
+            image.pixels.chunks_exact(4).filter(|p| p[3] != 0).count(),
+            36
+        );
+
+        // Too small for a triangle
+        let mut image = transparent(1);
+        badge.draw(&mut image);
+        assert_eq!(image.pixels, [0; 4]);
+    }
+}
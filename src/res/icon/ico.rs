@@ -0,0 +1,470 @@
+#[cfg(feature = "png")]
+use crate::ResError;
+#[cfg(feature = "png")]
+use crate::image::Image;
+#[cfg(feature = "png")]
+use crate::util::{push_u16, push_u32};
+use crate::util::{read_u16, read_u32, read_u32_be};
+
+/// Largest size of an icon image.
+const MAX_SIZE: u32 = 256;
+/// Images of this size or larger are stored PNG-compressed.
+#[cfg(feature = "png")]
+const PNG_SIZE: u32 = 256;
+const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+/// Size of an image of an icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub width: u32,
+    pub height: u32,
+    /// The directory entry lists a different size than the image's header.
+    pub mismatch: bool,
+}
+
+/// Parses the `ICONDIR` of an `.ico` file and checks the header of each image.
+pub fn validate(data: &[u8]) -> Result<Vec<Entry>, &'static str> {
+    if data.starts_with(PNG_SIGNATURE) {
+        return Err("file is a PNG image, not an icon");
+    }
+    if data.starts_with(b"BM") {
+        return Err("file is a bitmap, not an icon");
+    }
+    if data.len() < 6 || read_u16(data, 0) != Some(0) {
+        return Err("invalid icon header");
+    }
+    match read_u16(data, 2) {
+        Some(1) => {}
+        Some(2) => return Err("file is a cursor, not an icon"),
+        _ => return Err("invalid icon header"),
+    }
+    let count = usize::from(read_u16(data, 4).unwrap_or_default());
+    if count == 0 {
+        return Err("icon has no images");
+    }
+    let directory_end = 6 + count * 16;
+    if data.len() < directory_end {
+        return Err("icon directory is truncated");
+    }
+
+    let mut entries = Vec::with_capacity(count);
+    for entry in data[6..directory_end].chunks_exact(16) {
+        // 0 means 256 pixels
+        let width = if entry[0] == 0 {
+            MAX_SIZE
+        } else {
+            u32::from(entry[0])
+        };
+        let height = if entry[1] == 0 {
+            MAX_SIZE
+        } else {
+            u32::from(entry[1])
+        };
+        let size = read_u32(entry, 8).unwrap_or_default() as usize;
+        let offset = read_u32(entry, 12).unwrap_or_default() as usize;
+
+        if size == 0 || offset < directory_end {
+            return Err("icon directory entry is invalid");
+        }
+        let image = offset
+            .checked_add(size)
+            .and_then(|end| data.get(offset..end))
+            .ok_or("image data is out of the file bounds")?;
+
+        let (image_width, image_height) = if image.starts_with(PNG_SIGNATURE) {
+            let width = read_u32_be(image, 16).ok_or("PNG image is truncated")?;
+            let height = read_u32_be(image, 20).ok_or("PNG image is truncated")?;
+            (width, height)
+        } else {
+            let header_size = read_u32(image, 0).ok_or("bitmap header is truncated")?;
+            if header_size < 40 || image.len() < header_size as usize {
+                return Err("bitmap header is invalid");
+            }
+            let bit_count = read_u16(image, 14).unwrap_or_default();
+            if !matches!(bit_count, 1 | 4 | 8 | 24 | 32) {
+                return Err("bitmap has an unsupported bit depth");
+            }
+            if !matches!(read_u32(image, 16), Some(BI_RGB | BI_BITFIELDS)) {
+                return Err("bitmap has an unsupported compression");
+            }
+            let width = read_u32(image, 4).unwrap_or_default();
+            // Includes the AND mask
+            let height = read_u32(image, 8).unwrap_or_default() / 2;
+            (width, height)
+        };
+
+        // The resource compiler and Windows use the image header
+        entries.push(Entry {
+            width: image_width,
+            height: image_height,
+            mismatch: (image_width, image_height) != (width, height),
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Decodes the images of an `.ico` file, keeping the highest bit depth of each size.
+#[cfg(feature = "png")]
+pub fn decode(data: &[u8]) -> Result<Vec<Frame>, ResError> {
+    let error = |e: &str| ResError::Parse(format!("icon: {e}"));
+    validate(data).map_err(error)?;
+
+    let mut frames: Vec<(u16, Frame)> = Vec::new();
+    let count = usize::from(read_u16(data, 4).unwrap_or_default());
+    for entry in data[6..6 + count * 16].chunks_exact(16) {
+        let size = read_u32(entry, 8).unwrap_or_default() as usize;
+        let offset = read_u32(entry, 12).unwrap_or_default() as usize;
+        let image = &data[offset..offset + size];
+
+        let (bit_count, frame) = if image.starts_with(PNG_SIGNATURE) {
+            let frame = Frame {
+                image: Image::from_png(image)?,
+                png: Some(image.to_vec()),
+            };
+            (32, frame)
+        } else {
+            let bit_count = read_u16(image, 14).unwrap_or_default();
+            let frame = Frame {
+                image: decode_dib(image).map_err(error)?,
+                png: None,
+            };
+            (bit_count, frame)
+        };
+
+        let size = |frame: &Frame| (frame.image.width, frame.image.height);
+        match frames.iter_mut().find(|(_, f)| size(f) == size(&frame)) {
+            Some(existing) if existing.0 < bit_count => *existing = (bit_count, frame),
+            Some(_) => {}
+            None => frames.push((bit_count, frame)),
+        }
+    }
+
+    Ok(frames.into_iter().map(|(_, frame)| frame).collect())
+}
+
+/// Decodes a DIB of an icon with its AND mask.
+#[cfg(feature = "png")]
+fn decode_dib(data: &[u8]) -> Result<Image, &'static str> {
+    const TRUNCATED: &str = "bitmap is truncated";
+
+    let header_size = read_u32(data, 0).unwrap_or_default() as usize;
+    let width = read_u32(data, 4).unwrap_or_default() as usize;
+    let height = read_u32(data, 8).unwrap_or_default() as usize / 2;
+    let bit_count = usize::from(read_u16(data, 14).unwrap_or_default());
+    let bitfields = read_u32(data, 16) == Some(BI_BITFIELDS);
+
+    let masks = if bitfields {
+        if bit_count != 32 {
+            return Err("bitmap has bit fields with an unsupported bit depth");
+        }
+        let mask = |offset| read_u32(data, offset).ok_or(TRUNCATED);
+        // Only the V3 header and later ones contain an alpha mask
+        let alpha = if header_size >= 56 {
+            mask(52)?
+        } else {
+            0xFF00_0000
+        };
+        Some([mask(40)?, mask(44)?, mask(48)?, alpha])
+    } else {
+        None
+    };
+    // The masks follow a plain info header
+    let header_end = if bitfields && header_size == 40 {
+        52
+    } else {
+        header_size
+    };
+
+    let palette_size = match (bit_count, read_u32(data, 32).unwrap_or_default()) {
+        (1 | 4 | 8, 0) => 1 << bit_count,
+        (1 | 4 | 8, used) => used as usize,
+        _ => 0,
+    };
+    let palette = data
+        .get(header_end..header_end + palette_size * 4)
+        .ok_or(TRUNCATED)?;
+
+    let stride = (width * bit_count).div_ceil(32) * 4;
+    let mask_stride = width.div_ceil(32) * 4;
+    let pixels_start = header_end + palette.len();
+    let rows = data
+        .get(pixels_start..pixels_start + stride * height)
+        .ok_or(TRUNCATED)?;
+    // Some 32-bit icons omit the mask
+    let mask =
+        data.get(pixels_start + rows.len()..pixels_start + rows.len() + mask_stride * height);
+
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for y in (0..height).rev() {
+        let row = &rows[y * stride..(y + 1) * stride];
+        for x in 0..width {
+            let [b, g, r, a] = match (bit_count, masks) {
+                (32, Some([red, green, blue, alpha])) => {
+                    let value = read_u32(row, x * 4).unwrap_or_default();
+                    [
+                        channel(value, blue),
+                        channel(value, green),
+                        channel(value, red),
+                        channel(value, alpha),
+                    ]
+                }
+                (32, None) => [row[x * 4], row[x * 4 + 1], row[x * 4 + 2], row[x * 4 + 3]],
+                (24, _) => [row[x * 3], row[x * 3 + 1], row[x * 3 + 2], 0xFF],
+                _ => {
+                    let bit = x * bit_count;
+                    let index =
+                        (row[bit / 8] >> (8 - bit_count - bit % 8)) & ((1 << bit_count) - 1) as u8;
+                    let color = palette
+                        .get(usize::from(index) * 4..usize::from(index) * 4 + 3)
+                        .ok_or("bitmap references a missing palette color")?;
+                    [color[0], color[1], color[2], 0xFF]
+                }
+            };
+            let masked =
+                mask.is_some_and(|mask| mask[y * mask_stride + x / 8] & (0x80 >> (x % 8)) != 0);
+            pixels.extend([r, g, b, if masked && bit_count != 32 { 0 } else { a }]);
+        }
+    }
+
+    // 32-bit images without alpha rely on the mask
+    if bit_count == 32 && pixels.chunks_exact(4).all(|p| p[3] == 0) {
+        for (i, pixel) in pixels.chunks_exact_mut(4).enumerate() {
+            let (x, y) = (i % width, height - 1 - i / width);
+            let masked =
+                mask.is_some_and(|mask| mask[y * mask_stride + x / 8] & (0x80 >> (x % 8)) != 0);
+            pixel[3] = if masked { 0 } else { 0xFF };
+        }
+    }
+
+    Ok(Image {
+        width: width as u32,
+        height: height as u32,
+        pixels,
+    })
+}
+
+/// Extracts a color channel of a bit fields pixel, scaled to 8 bits.
+#[cfg(feature = "png")]
+fn channel(value: u32, mask: u32) -> u8 {
+    if mask == 0 {
+        return 0;
+    }
+    let max = u64::from(mask >> mask.trailing_zeros());
+    let bits = u64::from((value & mask) >> mask.trailing_zeros());
+    (bits * 0xFF / max) as u8
+}
+
+/// Image of an icon with its original PNG data, if any.
+#[cfg(feature = "png")]
+pub struct Frame {
+    pub image: Image,
+    pub png: Option<Vec<u8>>,
+}
+
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+
+/// Builds an `.ico` file from the frames, largest first.
+///
+/// Large frames are stored as PNG while small ones as 32-bit DIB with an AND mask
+/// for compatibility.
+#[cfg(feature = "png")]
+pub fn encode(mut frames: Vec<Frame>) -> Result<Vec<u8>, ResError> {
+    if frames.is_empty() {
+        return Err(ResError::Custom("Icon must have at least one frame!"));
+    }
+    for frame in &frames {
+        let Image { width, height, .. } = frame.image;
+        if width == 0 || height == 0 || width > MAX_SIZE || height > MAX_SIZE {
+            return Err(ResError::Custom(
+                "Icon frame must be 1 to 256 pixels in size!",
+            ));
+        }
+    }
+    frames.sort_by(|a, b| {
+        let size = |frame: &Frame| (frame.image.width, frame.image.height);
+        size(b).cmp(&size(a))
+    });
+    if frames.windows(2).any(|pair| {
+        (pair[0].image.width, pair[0].image.height) == (pair[1].image.width, pair[1].image.height)
+    }) {
+        return Err(ResError::Custom(
+            "Icon contains multiple frames of the same size!",
+        ));
+    }
+
+    let mut images = Vec::with_capacity(frames.len());
+    for frame in frames {
+        let image = &frame.image;
+        let data = if image.width >= PNG_SIZE || image.height >= PNG_SIZE {
+            match frame.png {
+                Some(png) => png,
+                None => image.to_png()?,
+            }
+        } else {
+            dib(image)
+        };
+        images.push((image.width, image.height, data));
+    }
+
+    // ICONDIR
+    let mut data = Vec::new();
+    push_u16(&mut data, 0); // Reserved
+    push_u16(&mut data, 1); // Type: icon
+    push_u16(&mut data, images.len() as u16);
+
+    // ICONDIRENTRY
+    let mut offset = 6 + 16 * images.len();
+    for (width, height, image) in &images {
+        data.push(*width as u8); // 256 is stored as 0
+        data.push(*height as u8);
+        data.push(0); // Color count
+        data.push(0); // Reserved
+        push_u16(&mut data, 1); // Planes
+        push_u16(&mut data, 32); // Bit count
+        push_u32(&mut data, image.len() as u32);
+        push_u32(&mut data, offset as u32);
+        offset += image.len();
+    }
+
+    for (_, _, image) in images {
+        data.extend(image);
+    }
+    Ok(data)
+}
+
+/// Encodes the image as a 32-bit DIB followed by the AND mask.
+#[cfg(feature = "png")]
+fn dib(image: &Image) -> Vec<u8> {
+    let pixels = image.to_bgra_bottom_up();
+    let mask_stride = (image.width as usize).div_ceil(32) * 4;
+
+    let mut data = Vec::with_capacity(40 + pixels.len() + mask_stride * image.height as usize);
+
+    // BITMAPINFOHEADER
+    push_u32(&mut data, 40);
+    push_u32(&mut data, image.width);
+    push_u32(&mut data, image.height * 2); // Includes the AND mask
+    push_u16(&mut data, 1); // Planes
+    push_u16(&mut data, 32); // Bit count
+    push_u32(&mut data, 0); // Compression: BI_RGB
+    push_u32(&mut data, 0); // Image size
+    data.extend([0; 16]); // Resolution & colors
+
+    // AND mask, set where the image is fully transparent
+    let mut mask = vec![0; mask_stride * image.height as usize];
+    for (y, row) in pixels.chunks_exact(image.width as usize * 4).enumerate() {
+        for (x, pixel) in row.chunks_exact(4).enumerate() {
+            if pixel[3] == 0 {
+                mask[y * mask_stride + x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+    }
+
+    data.extend(pixels);
+    data.extend(mask);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "png")]
+    fn frame(size: u32) -> Frame {
+        let mut pixels = vec![0xFF; (size * size * 4) as usize];
+        pixels[3] = 0; // Transparent top-left pixel
+        Frame {
+            image: Image {
+                width: size,
+                height: size,
+                pixels,
+            },
+            png: None,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn ico_layout() {
+        let data = encode(vec![frame(16), frame(256)]).unwrap();
+
+        assert_eq!(&data[..6], &[0, 0, 1, 0, 2, 0]);
+        // 256px first as PNG
+        assert_eq!(&data[6..8], &[0, 0]);
+        assert_eq!(&data[38..46], b"\x89PNG\r\n\x1a\n");
+
+        // 16px as DIB with the mask bit of the top-left pixel set
+        let entry = &data[22..38];
+        assert_eq!(entry[0], 16);
+        let size = u32::from_le_bytes(entry[8..12].try_into().unwrap()) as usize;
+        let offset = u32::from_le_bytes(entry[12..16].try_into().unwrap()) as usize;
+        assert_eq!(size, 40 + 16 * 16 * 4 + 16 * 4);
+        assert_eq!(offset + size, data.len());
+        assert_eq!(&data[data.len() - 4..], &[0x80, 0, 0, 0]);
+
+        let decoded = decode(&data).unwrap();
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[1].image.pixels, frame(16).image.pixels);
+
+        assert!(encode(vec![frame(16), frame(16)]).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn bitfields() {
+        // A 1x1 bitmap with its pixel stored in the RGBA order
+        let mut dib = Vec::new();
+        for value in [40, 1, 2] {
+            dib.extend(u32::to_le_bytes(value));
+        }
+        dib.extend([1, 0, 32, 0]);
+        dib.extend(BI_BITFIELDS.to_le_bytes());
+        dib.extend([0; 20]);
+        for mask in [0xFF, 0xFF00, 0xFF_0000] {
+            dib.extend(u32::to_le_bytes(mask));
+        }
+        dib.extend([0x10, 0x20, 0x30, 0x40]);
+        dib.extend([0; 4]);
+
+        let image = decode_dib(&dib).unwrap();
+        assert_eq!(image.pixels, [0x10, 0x20, 0x30, 0x40]);
+
+        dib[14] = 8;
+        assert!(decode_dib(&dib).is_err());
+    }
+
+    #[test]
+    fn validation() {
+        // A 2x2 icon with a 32-bit bitmap and its AND mask
+        let mut icon = vec![0, 0, 1, 0, 1, 0];
+        icon.extend([2, 2, 0, 0, 1, 0, 32, 0]);
+        icon.extend(((40 + 16 + 8) as u32).to_le_bytes());
+        icon.extend(22u32.to_le_bytes());
+        icon.extend(40u32.to_le_bytes());
+        icon.extend(2u32.to_le_bytes());
+        icon.extend(4u32.to_le_bytes());
+        icon.extend([1, 0, 32, 0]);
+        icon.extend([0; 24 + 16 + 8]);
+
+        assert_eq!(
+            validate(&icon),
+            Ok(vec![Entry {
+                width: 2,
+                height: 2,
+                mismatch: false,
+            }])
+        );
+
+        // Directory entries are not trusted
+        icon[6] = 0;
+        assert!(validate(&icon).is_ok_and(|entries| entries[0].width == 2 && entries[0].mismatch));
+        icon[6] = 2;
+        assert!(validate(&icon[..icon.len() - 1]).is_err());
+        assert!(validate(PNG_SIGNATURE).is_err());
+
+        icon[22 + 14] = 16;
+        assert_eq!(validate(&icon), Err("bitmap has an unsupported bit depth"));
+    }
+}
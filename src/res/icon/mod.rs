@@ -0,0 +1,584 @@
+//! Bitmap icon resource. ([`ICON`](crate::icon::Icon))
+
+#[cfg(feature = "png")]
+mod badge;
+mod ico;
+mod pe;
+#[cfg(feature = "svg")]
+mod svg;
+
+use crate::custom::ResType;
+use crate::{DataSource, ResError, ResId, ResWriter, Resource, util};
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "png")]
+pub use badge::{Badge, BadgeStyle};
+
+/// Bitmap icon resource. (`ICON`)
+///
+/// ### Example:
+/// ```
+/// # use winscribe::icon::Icon;
+/// Icon::app("assets/application.ico");
+/// // From memory, ex: `include_bytes!("splash.ico")` or a downloaded icon:
+/// # let downloaded: Vec<u8> = Vec::new();
+/// Icon::from_data("SPLASH", downloaded);
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/icon-resource>
+pub struct Icon {
+    id: ResId,
+    language: Option<u16>,
+    source: IconSource,
+    #[cfg(feature = "png")]
+    badge: Option<Badge>,
+}
+
+impl Icon {
+    /// Id of the default application icon. (`IDI_APPLICATION`)
+    pub const APP_ID: u16 = 32512;
+
+    /// Creates a new icon resource.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `path`: Path to the `.ico` file.
+    pub fn new<N, P>(id: N, path: P) -> Self
+    where
+        N: Into<ResId>,
+        P: Into<PathBuf>,
+    {
+        Self::from_data(id, path.into())
+    }
+
+    /// Creates a new icon resource from the content of an `.ico` file.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `data`: Path to the `.ico` file or its in-memory bytes.
+    pub fn from_data<N, D>(id: N, data: D) -> Self
+    where
+        N: Into<ResId>,
+        D: Into<DataSource>,
+    {
+        Self::from_source(id, IconSource::Data(data.into()))
+    }
+
+    /// Creates a new icon resource from separate images of each size.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `frames`: Images of the icon.
+    #[cfg(feature = "png")]
+    pub fn from_frames<N: Into<ResId>>(id: N, frames: IconFrames) -> Self {
+        Self::from_source(id, IconSource::Frames(frames))
+    }
+
+    /// Creates a new icon resource from an icon of an executable or DLL.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `icon`: Location of the icon.
+    pub fn from_executable<N: Into<ResId>>(id: N, icon: ExecutableIcon) -> Self {
+        Self::from_source(id, IconSource::Executable(icon))
+    }
+
+    /// Default application icon. (`IDI_APPLICATION`)
+    pub fn app<P: Into<PathBuf>>(path: P) -> Self {
+        Self::new(Self::APP_ID, path)
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Sets the badge drawn onto every frame of the icon. `None` leaves the icon unchanged.
+    #[cfg(feature = "png")]
+    pub fn with_badge<B: Into<Option<Badge>>>(mut self, badge: B) -> Self {
+        self.badge = badge.into();
+        self
+    }
+
+    fn from_source<N: Into<ResId>>(id: N, source: IconSource) -> Self {
+        Self {
+            id: id.into(),
+            language: None,
+            source,
+            #[cfg(feature = "png")]
+            badge: None,
+        }
+    }
+
+    /// Path to the `.ico` file, with the badge drawn if set.
+    fn path(&self) -> Result<PathBuf, ResError> {
+        #[cfg(feature = "png")]
+        return badged_path(&self.source, self.badge.as_ref());
+        #[cfg(not(feature = "png"))]
+        self.source.path()
+    }
+}
+
+impl Resource for Icon {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        let path = self.path()?;
+        write_group(&self.id, self.language, &path, writer)
+    }
+}
+
+/// Writes an icon group statement of the `.ico` file.
+fn write_group(
+    id: &ResId,
+    language: Option<u16>,
+    path: &Path,
+    writer: &mut ResWriter,
+) -> Result<(), ResError> {
+    writer.define(ResType::GROUP_ICON, id.clone(), language)?;
+    writer.line(format!("{id} ICON \"{}\"", util::escape_path(path)?));
+    Ok(())
+}
+
+/// Application icon with the icons of the associated document types.
+///
+/// Explorer shows the first icon group of the resource table as the icon of the executable,
+/// and file associations refer to icons by their index in it. The icons are numbered in order
+/// starting from 1, so the application icon comes first. Named icon groups are ordered before
+/// every numbered one and would shift the indexes.
+///
+/// The id and index of each icon are added to the generated resource ids as `ICON_{NAME}` and
+/// `ICON_INDEX_{NAME}`. (ex: `ICON_APP`, `ICON_INDEX_APP` and `ICON_INDEX_TEXT`)
+///
+/// ### Example:
+/// ```
+/// # use winscribe::icon::IconSet;
+/// let icons = IconSet::new("assets/application.ico")
+///     .with_document("text", "assets/text.ico")
+///     .with_document("project", "assets/project.ico");
+///
+/// // DefaultIcon = app.exe,2
+/// assert_eq!(icons.index("project"), Some(2));
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/shell/how-to-assign-a-custom-icon-to-a-file-type>
+pub struct IconSet {
+    /// Name and source of the icons, the application icon first.
+    icons: Vec<(String, IconSource)>,
+    language: Option<u16>,
+    #[cfg(feature = "png")]
+    badge: Option<Badge>,
+}
+
+impl IconSet {
+    /// Name of the application icon.
+    pub const APP: &str = "app";
+
+    /// Creates a new icon set.
+    ///
+    /// * `app`: Icon of the application, named [`APP`](Self::APP).
+    pub fn new<S: Into<IconSource>>(app: S) -> Self {
+        Self {
+            icons: vec![(Self::APP.to_string(), app.into())],
+            language: None,
+            #[cfg(feature = "png")]
+            badge: None,
+        }
+    }
+
+    /// Adds the icon of a document type.
+    ///
+    /// * `name`: Unique name of the icon, used for the constants of its id and index.
+    /// * `icon`: Icon of the documents.
+    pub fn with_document<T: Into<String>, S: Into<IconSource>>(mut self, name: T, icon: S) -> Self {
+        self.icons.push((name.into(), icon.into()));
+        self
+    }
+
+    /// Sets the Windows Language Code of the icons.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Sets the badge drawn onto every frame of the application icon. `None` leaves the icon
+    /// unchanged.
+    ///
+    /// The icons of the documents are never badged, as they stand for the files.
+    #[cfg(feature = "png")]
+    pub fn with_badge<B: Into<Option<Badge>>>(mut self, badge: B) -> Self {
+        self.badge = badge.into();
+        self
+    }
+
+    /// Position of the icon in the resource table. (ex: for `ExtractIconW` or `DefaultIcon`)
+    ///
+    /// Names are case-insensitive.
+    pub fn index(&self, name: &str) -> Option<usize> {
+        self.icons
+            .iter()
+            .position(|(n, _)| n.eq_ignore_ascii_case(name))
+    }
+}
+
+impl Resource for IconSet {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        for (index, (name, source)) in self.icons.iter().enumerate() {
+            if self.index(name) != Some(index) {
+                return Err(ResError::Duplicate(format!(
+                    "Icon \"{name}\" of the icon set"
+                )));
+            }
+
+            #[cfg(feature = "png")]
+            let path = badged_path(source, self.badge.as_ref().filter(|_| index == 0))?;
+            #[cfg(not(feature = "png"))]
+            let path = source.path()?;
+
+            let id = index as u16 + 1;
+            write_group(&ResId::Int(id), self.language, &path, writer)?;
+            writer.icon_index(name, id, index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Source of the images of an icon.
+///
+/// Paths convert into [`Data`](Self::Data), so they can be passed directly.
+pub enum IconSource {
+    /// Path to the `.ico` file or its in-memory bytes.
+    Data(DataSource),
+    /// Icon of an executable or DLL.
+    Executable(ExecutableIcon),
+    /// Separate images of each size.
+    #[cfg(feature = "png")]
+    Frames(IconFrames),
+}
+
+impl IconSource {
+    /// Path to the `.ico` file.
+    fn path(&self) -> Result<PathBuf, ResError> {
+        match self {
+            Self::Data(data) => {
+                validate(&data.name(), &data.bytes()?)?;
+                data.path("icon", "ico")
+            }
+            _ => util::hashed_out_file("icon", "ico", &self.data()?),
+        }
+    }
+
+    /// Content of the validated `.ico` file.
+    fn data(&self) -> Result<Vec<u8>, ResError> {
+        match self {
+            Self::Data(data) => {
+                let bytes = data.bytes()?.into_owned();
+                validate(&data.name(), &bytes)?;
+                Ok(bytes)
+            }
+            Self::Executable(icon) => {
+                let data = icon.extract()?;
+                validate(&icon.path.display().to_string(), &data)?;
+                Ok(data)
+            }
+            #[cfg(feature = "png")]
+            Self::Frames(frames) => frames.encode(),
+        }
+    }
+}
+
+impl From<DataSource> for IconSource {
+    fn from(value: DataSource) -> Self {
+        Self::Data(value)
+    }
+}
+
+impl From<PathBuf> for IconSource {
+    fn from(value: PathBuf) -> Self {
+        Self::Data(value.into())
+    }
+}
+
+impl From<&Path> for IconSource {
+    fn from(value: &Path) -> Self {
+        Self::Data(value.into())
+    }
+}
+
+impl From<&str> for IconSource {
+    fn from(value: &str) -> Self {
+        Self::Data(PathBuf::from(value).into())
+    }
+}
+
+impl From<String> for IconSource {
+    fn from(value: String) -> Self {
+        Self::Data(PathBuf::from(value).into())
+    }
+}
+
+impl From<ExecutableIcon> for IconSource {
+    fn from(value: ExecutableIcon) -> Self {
+        Self::Executable(value)
+    }
+}
+
+#[cfg(feature = "png")]
+impl From<IconFrames> for IconSource {
+    fn from(value: IconFrames) -> Self {
+        Self::Frames(value)
+    }
+}
+
+/// Icon group of an executable or DLL.
+///
+/// It is read from the PE file, so it works on any host.
+///
+/// ### Example:
+/// ```
+/// # use winscribe::icon::{ExecutableIcon, Icon};
+/// // First icon, as displayed by Explorer
+/// Icon::from_executable(Icon::APP_ID, ExecutableIcon::index("vendor/tool.exe", 0));
+/// // Icon group with the id 101
+/// Icon::from_executable("SETTINGS", ExecutableIcon::id("vendor/tool.dll", 101));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExecutableIcon {
+    path: PathBuf,
+    selector: pe::Selector,
+}
+
+impl ExecutableIcon {
+    /// Selects the icon group by its position in the resource table, like `ExtractIconW`.
+    ///
+    /// * `path`: Path to the `.exe` or `.dll` file.
+    /// * `index`: Zero-based position of the icon group.
+    pub fn index<P: Into<PathBuf>>(path: P, index: usize) -> Self {
+        Self {
+            path: path.into(),
+            selector: pe::Selector::Index(index),
+        }
+    }
+
+    /// Selects the icon group by its resource id.
+    ///
+    /// * `path`: Path to the `.exe` or `.dll` file.
+    /// * `id`: Name or number of the `RT_GROUP_ICON` resource.
+    pub fn id<P: Into<PathBuf>, N: Into<ResId>>(path: P, id: N) -> Self {
+        Self {
+            path: path.into(),
+            selector: pe::Selector::Id(id.into()),
+        }
+    }
+
+    /// Reconstructs the `.ico` file of the icon group.
+    fn extract(&self) -> Result<Vec<u8>, ResError> {
+        pe::extract_icon(&std::fs::read(&self.path)?, &self.selector)
+            .map_err(|e| ResError::Parse(format!("executable \"{}\": {e}", self.path.display())))
+    }
+}
+
+/// Sizes an icon should contain to look sharp at the common DPI scales.
+const RECOMMENDED_SIZES: [u32; 5] = [16, 24, 32, 48, 256];
+
+/// Checks the `.ico` data and prints a warning if it misses any of the recommended sizes
+/// or its directory doesn't match the images.
+///
+/// * `name`: Name of the source of the icon, used in the messages.
+fn validate(name: &str, data: &[u8]) -> Result<(), ResError> {
+    let entries =
+        ico::validate(data).map_err(|e| ResError::Parse(format!("icon \"{name}\": {e}")))?;
+    if entries.iter().any(|e| e.mismatch) {
+        util::warning(format!(
+            "Icon \"{name}\" has directory entries which don't match the size of their images"
+        ));
+    }
+
+    let missing: Vec<String> = RECOMMENDED_SIZES
+        .into_iter()
+        .filter(|size| {
+            !entries
+                .iter()
+                .any(|e| e.width == *size && e.height == *size)
+        })
+        .map(|size| format!("{size}x{size}"))
+        .collect();
+    if !missing.is_empty() {
+        util::warning(format!(
+            "Icon \"{name}\" is missing the recommended sizes: {}",
+            missing.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// Path to the `.ico` file of the source, with the badge drawn if set.
+#[cfg(feature = "png")]
+fn badged_path(source: &IconSource, badge: Option<&Badge>) -> Result<PathBuf, ResError> {
+    let Some(badge) = badge else {
+        return source.path();
+    };
+    let mut frames = ico::decode(&source.data()?)?;
+    for frame in &mut frames {
+        badge.draw(&mut frame.image);
+        frame.png = None;
+    }
+    util::hashed_out_file("icon", "ico", &ico::encode(frames)?)
+}
+
+/// Standard icon sizes used by Windows at the various DPI scales.
+#[cfg(feature = "png")]
+const STANDARD_SIZES: [u32; 8] = [16, 20, 24, 32, 40, 48, 64, 256];
+
+/// Images of an icon, one for each size.
+///
+/// Frames of 256 pixels are stored PNG-compressed, smaller ones as bitmaps with a
+/// transparency mask to be displayed correctly everywhere.
+///
+/// ### Example:
+/// ```
+/// # use winscribe::icon::{Icon, IconFrames};
+/// Icon::from_frames(
+///     "APP_ICON",
+///     IconFrames::new()
+///         .with_frame("icons/16.bmp")
+///         .with_frame("icons/32.png")
+///         .with_frame("icons/256.png"),
+/// );
+///
+/// // Resampled from a single image with a pixel-hinted 16x16 variant:
+/// Icon::from_frames(
+///     Icon::APP_ID,
+///     IconFrames::from_image("icons/logo-1024.png").with_frame("icons/logo-16.png"),
+/// );
+/// ```
+#[cfg(feature = "png")]
+#[derive(Debug, Clone, Default)]
+pub struct IconFrames {
+    source: Option<FrameSource>,
+    frames: Vec<PathBuf>,
+}
+
+/// Source of the generated frames.
+#[cfg(feature = "png")]
+#[derive(Debug, Clone)]
+enum FrameSource {
+    Image(PathBuf),
+    #[cfg(feature = "svg")]
+    Svg(PathBuf),
+}
+
+#[cfg(feature = "png")]
+impl IconFrames {
+    /// Creates a new, empty set of frames.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates the frames of the standard sizes (16, 20, 24, 32, 40, 48, 64 and 256 pixels)
+    /// by resampling a single square image.
+    ///
+    /// Frames added with [`with_frame`](Self::with_frame) override the resampled one of the same size.
+    ///
+    /// * `path`: Path to the `.png` or 24/32-bit `.bmp` file, ideally 256 pixels or larger.
+    pub fn from_image<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            source: Some(FrameSource::Image(path.into())),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Creates the frames of the standard sizes (16, 20, 24, 32, 40, 48, 64 and 256 pixels)
+    /// by rendering an SVG document at each size.
+    ///
+    /// Frames added with [`with_frame`](Self::with_frame) override the rendered one of the same size.
+    /// Non-square documents are centered. Text must be converted to paths, as no fonts are loaded.
+    ///
+    /// * `path`: Path to the `.svg` file.
+    #[cfg(feature = "svg")]
+    pub fn from_svg<P: Into<PathBuf>>(path: P) -> Self {
+        Self {
+            source: Some(FrameSource::Svg(path.into())),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Adds a frame. Its size must be unique and at most 256 pixels.
+    ///
+    /// * `path`: Path to the `.png` or 24/32-bit `.bmp` file.
+    pub fn with_frame<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.frames.push(path.into());
+        self
+    }
+
+    /// Encodes the frames as an `.ico` file.
+    fn encode(&self) -> Result<Vec<u8>, ResError> {
+        let mut frames = Vec::with_capacity(self.frames.len());
+        for path in &self.frames {
+            frames.push(load_frame(path)?);
+        }
+
+        let missing: Vec<u32> = STANDARD_SIZES
+            .into_iter()
+            .filter(|size| !frames.iter().any(|frame| frame.image.width == *size))
+            .collect();
+
+        match &self.source {
+            Some(FrameSource::Image(path)) => {
+                let source = load_frame(path)?.image;
+                if source.width != source.height {
+                    return Err(ResError::Parse(format!(
+                        "icon source \"{}\": image must be square",
+                        path.display()
+                    )));
+                }
+                if source.width < 256 {
+                    util::warning(format!(
+                        "Icon source \"{}\" is smaller than 256 pixels, larger sizes will be blurry",
+                        path.display()
+                    ));
+                }
+
+                for size in missing {
+                    frames.push(ico::Frame {
+                        image: source.resize(size, size),
+                        png: None,
+                    });
+                }
+            }
+            #[cfg(feature = "svg")]
+            Some(FrameSource::Svg(path)) => {
+                let error = |e: ResError| {
+                    ResError::Parse(format!("icon source \"{}\": {e}", path.display()))
+                };
+                let source = svg::Svg::parse(&std::fs::read(path)?).map_err(error)?;
+
+                for size in missing {
+                    frames.push(ico::Frame {
+                        image: source.render(size).map_err(error)?,
+                        png: None,
+                    });
+                }
+            }
+            None => {}
+        }
+
+        ico::encode(frames)
+    }
+}
+
+/// Decodes a `.png` or `.bmp` image.
+#[cfg(feature = "png")]
+fn load_frame(path: &Path) -> Result<ico::Frame, ResError> {
+    use crate::image::Image;
+
+    let data = std::fs::read(path)?;
+    let error = |e: ResError| ResError::Parse(format!("icon frame \"{}\": {e}", path.display()));
+
+    if data.starts_with(b"\x89PNG") {
+        Ok(ico::Frame {
+            image: Image::from_png(&data).map_err(error)?,
+            png: Some(data),
+        })
+    } else {
+        Ok(ico::Frame {
+            image: Image::from_bmp(&data).map_err(error)?,
+            png: None,
+        })
+    }
+}
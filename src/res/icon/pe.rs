@@ -0,0 +1,278 @@
+use crate::ResId;
+use crate::util::{push_u16, push_u32, read_u16, read_u32};
+
+/// Resource type of an icon image. (`RT_ICON`)
+const RT_ICON: u32 = 3;
+/// Resource type of an icon group. (`RT_GROUP_ICON`)
+const RT_GROUP_ICON: u32 = 14;
+/// Index of the resource table in the data directories.
+const RESOURCE_DIRECTORY: usize = 2;
+
+/// Selects an icon group of an executable.
+#[derive(Debug, Clone)]
+pub enum Selector {
+    /// Position in the resource table, like `ExtractIconW`.
+    Index(usize),
+    /// Id of the `RT_GROUP_ICON` resource.
+    Id(ResId),
+}
+
+/// Reconstructs an `.ico` file from an icon group in the resources of a PE file.
+pub fn extract_icon(data: &[u8], selector: &Selector) -> Result<Vec<u8>, &'static str> {
+    let resources = Resources::parse(data)?;
+
+    let groups = resources.entries(RT_GROUP_ICON)?;
+    let group = match selector {
+        Selector::Index(index) => groups.get(*index),
+        Selector::Id(id) => groups.iter().find(|(name, _)| *name == id.normalized()),
+    }
+    .ok_or("icon group not found in the executable")?;
+    let group = resources.data(group.1)?;
+
+    // GRPICONDIR
+    if read_u16(group, 0) != Some(0) || read_u16(group, 2) != Some(1) {
+        return Err("invalid icon group header");
+    }
+    let count = usize::from(read_u16(group, 4).unwrap_or_default());
+    let icons = resources.entries(RT_ICON)?;
+
+    let mut images = Vec::with_capacity(count);
+    for index in 0..count {
+        // GRPICONDIRENTRY
+        let entry = group
+            .get(6 + index * 14..6 + (index + 1) * 14)
+            .ok_or("icon group is truncated")?;
+        let id = ResId::Int(read_u16(entry, 12).unwrap_or_default());
+        let icon = icons
+            .iter()
+            .find(|(name, _)| *name == id)
+            .ok_or("icon group references a missing icon")?;
+        images.push((&entry[..12], resources.data(icon.1)?));
+    }
+
+    // ICONDIR
+    let mut ico = Vec::new();
+    push_u16(&mut ico, 0); // Reserved
+    push_u16(&mut ico, 1); // Type: icon
+    push_u16(&mut ico, images.len() as u16);
+
+    // ICONDIRENTRY
+    let mut offset = 6 + 16 * images.len();
+    for (entry, image) in &images {
+        ico.extend(&entry[..8]); // Size, colors, planes & bit count
+        push_u32(&mut ico, image.len() as u32);
+        push_u32(&mut ico, offset as u32);
+        offset += image.len();
+    }
+
+    for (_, image) in images {
+        ico.extend(image);
+    }
+    Ok(ico)
+}
+
+/// Resource section of a PE file.
+struct Resources<'a> {
+    data: &'a [u8],
+    /// Sections' virtual address, virtual size and file offset.
+    sections: Vec<(u32, u32, u32)>,
+    /// File offset of the root resource directory.
+    root: usize,
+}
+
+impl<'a> Resources<'a> {
+    fn parse(data: &'a [u8]) -> Result<Self, &'static str> {
+        if !data.starts_with(b"MZ") {
+            return Err("not an executable file");
+        }
+        let header = read_u32(data, 0x3C).ok_or("executable header is truncated")? as usize;
+        if data.get(header..header + 4) != Some(b"PE\0\0") {
+            return Err("not a PE executable");
+        }
+
+        let section_count = usize::from(read_u16(data, header + 6).unwrap_or_default());
+        let optional_size = usize::from(read_u16(data, header + 20).unwrap_or_default());
+        let optional = header + 24;
+        let directories = match read_u16(data, optional) {
+            Some(0x10B) => optional + 96,  // PE32
+            Some(0x20B) => optional + 112, // PE32+
+            _ => return Err("unknown optional header format"),
+        };
+        if read_u32(data, directories - 4).unwrap_or_default() as usize <= RESOURCE_DIRECTORY {
+            return Err("executable has no resources");
+        }
+        let resource_rva = read_u32(data, directories + RESOURCE_DIRECTORY * 8)
+            .ok_or("executable header is truncated")?;
+        if resource_rva == 0 {
+            return Err("executable has no resources");
+        }
+
+        let mut sections = Vec::with_capacity(section_count);
+        for index in 0..section_count {
+            let section = optional + optional_size + index * 40;
+            let virtual_size = read_u32(data, section + 8).ok_or("section table is truncated")?;
+            let address = read_u32(data, section + 12).ok_or("section table is truncated")?;
+            let raw_size = read_u32(data, section + 16).ok_or("section table is truncated")?;
+            let offset = read_u32(data, section + 20).ok_or("section table is truncated")?;
+            sections.push((address, virtual_size.max(raw_size), offset));
+        }
+
+        let mut resources = Self {
+            data,
+            sections,
+            root: 0,
+        };
+        resources.root = resources.offset(resource_rva)?;
+        Ok(resources)
+    }
+
+    /// Converts a relative virtual address to a file offset.
+    fn offset(&self, rva: u32) -> Result<usize, &'static str> {
+        self.sections
+            .iter()
+            .find(|(address, size, _)| rva >= *address && rva - address < *size)
+            .and_then(|(address, _, offset)| {
+                (*offset as usize).checked_add((rva - address) as usize)
+            })
+            .ok_or("resource is outside of the sections")
+    }
+
+    /// Entries of the directory of the resource type with their first language's data entry.
+    fn entries(&self, type_id: u32) -> Result<Vec<(ResId, usize)>, &'static str> {
+        let Some(directory) = self
+            .directory(self.root)?
+            .into_iter()
+            .find(|(name, _)| *name == ResId::Int(type_id as u16))
+            .map(|(_, offset)| offset)
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut entries = Vec::new();
+        for (name, subdirectory) in self.directory(directory)? {
+            // Take the first language
+            let (_, data) = self
+                .directory(subdirectory)?
+                .into_iter()
+                .next()
+                .ok_or("resource has no languages")?;
+            entries.push((name, data));
+        }
+        Ok(entries)
+    }
+
+    /// Names and offsets of the entries in a resource directory.
+    fn directory(&self, offset: usize) -> Result<Vec<(ResId, usize)>, &'static str> {
+        const TRUNCATED: &str = "resource directory is truncated";
+        const HIGH_BIT: u32 = 0x8000_0000;
+
+        let named = read_u16(self.data, offset + 12).ok_or(TRUNCATED)?;
+        let ids = read_u16(self.data, offset + 14).ok_or(TRUNCATED)?;
+
+        let mut entries = Vec::new();
+        for index in 0..usize::from(named) + usize::from(ids) {
+            let entry = offset + 16 + index * 8;
+            let name = read_u32(self.data, entry).ok_or(TRUNCATED)?;
+            let target = read_u32(self.data, entry + 4).ok_or(TRUNCATED)?;
+
+            let name = if name & HIGH_BIT != 0 {
+                let string = self.root + (name & !HIGH_BIT) as usize;
+                let length = usize::from(read_u16(self.data, string).ok_or(TRUNCATED)?);
+                let units: Vec<u16> = (0..length)
+                    .map(|i| read_u16(self.data, string + 2 + i * 2).ok_or(TRUNCATED))
+                    .collect::<Result<_, _>>()?;
+                ResId::Name(String::from_utf16_lossy(&units)).normalized()
+            } else {
+                ResId::Int(name as u16)
+            };
+            entries.push((name, self.root + (target & !HIGH_BIT) as usize));
+        }
+        Ok(entries)
+    }
+
+    /// Content of a resource data entry.
+    fn data(&self, entry: usize) -> Result<&'a [u8], &'static str> {
+        let rva = read_u32(self.data, entry).ok_or("resource data entry is truncated")?;
+        let size = read_u32(self.data, entry + 4).ok_or("resource data entry is truncated")?;
+        let offset = self.offset(rva)?;
+        self.data
+            .get(offset..offset + size as usize)
+            .ok_or("resource data is out of the file bounds")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds a PE32 file with one icon group `MAIN` of one 1x1 icon.
+    fn executable() -> Vec<u8> {
+        const SECTION_RVA: u32 = 0x1000;
+        const SECTION_OFFSET: usize = 0x200;
+
+        let mut data = vec![0; SECTION_OFFSET];
+        data[..2].copy_from_slice(b"MZ");
+        data[0x3C] = 0x40;
+        data[0x40..0x44].copy_from_slice(b"PE\0\0");
+        data[0x46] = 1; // Section count
+        data[0x54] = 224; // Optional header size
+        data[0x58..0x5A].copy_from_slice(&0x10Bu16.to_le_bytes());
+        data[0x58 + 92] = 16; // Number of data directories
+        data[0x58 + 96 + 16..0x58 + 96 + 20].copy_from_slice(&SECTION_RVA.to_le_bytes());
+        let section = 0x58 + 224;
+        data[section + 8..section + 12].copy_from_slice(&0x200u32.to_le_bytes());
+        data[section + 12..section + 16].copy_from_slice(&SECTION_RVA.to_le_bytes());
+        data[section + 20..section + 24].copy_from_slice(&(SECTION_OFFSET as u32).to_le_bytes());
+
+        let directory = |rsrc: &mut Vec<u8>, named: u16, entries: &[(u32, u32)]| {
+            rsrc.extend([0; 12]);
+            push_u16(rsrc, named);
+            push_u16(rsrc, entries.len() as u16 - named);
+            for (name, target) in entries {
+                push_u32(rsrc, *name);
+                push_u32(rsrc, *target);
+            }
+        };
+
+        // Offsets within the section
+        let mut rsrc = Vec::new();
+        directory(&mut rsrc, 0, &[(3, 0x8000_0020), (14, 0x8000_0038)]); // 0x00
+        directory(&mut rsrc, 0, &[(1, 0x8000_0050)]); // 0x20: RT_ICON
+        directory(&mut rsrc, 1, &[(0x8000_0098, 0x8000_0068)]); // 0x38: RT_GROUP_ICON
+        directory(&mut rsrc, 0, &[(0x409, 0x80)]); // 0x50: Icon language
+        directory(&mut rsrc, 0, &[(0x409, 0x90)]); // 0x68: Group language
+        push_u32(&mut rsrc, SECTION_RVA + 0xA4); // 0x80: Icon data entry
+        push_u32(&mut rsrc, 4);
+        rsrc.extend([0; 8]);
+        push_u32(&mut rsrc, SECTION_RVA + 0xA8); // 0x90: Group data entry
+        push_u32(&mut rsrc, 20);
+        push_u16(&mut rsrc, 4); // 0x98: Name
+        rsrc.extend("MAIN".encode_utf16().flat_map(u16::to_le_bytes));
+        rsrc.extend([0; 2]);
+        rsrc.extend([1, 2, 3, 4]); // 0xA4: Icon image
+        rsrc.extend([0, 0, 1, 0, 1, 0, 1, 1, 0, 0, 1, 0, 32, 0, 4, 0, 0, 0, 1, 0]); // 0xA8
+
+        data.extend(rsrc);
+        data
+    }
+
+    #[test]
+    fn extract() {
+        let executable = executable();
+        let ico = extract_icon(&executable, &Selector::Id(ResId::from("main"))).unwrap();
+        assert_eq!(
+            ico,
+            [
+                0, 0, 1, 0, 1, 0, 1, 1, 0, 0, 1, 0, 32, 0, 4, 0, 0, 0, 22, 0, 0, 0, 1, 2, 3, 4
+            ]
+        );
+        assert_eq!(extract_icon(&executable, &Selector::Index(0)).unwrap(), ico);
+        assert!(extract_icon(&executable, &Selector::Index(1)).is_err());
+
+        // Raw data pointer past the end of the address space
+        let mut executable = executable;
+        let section = 0x58 + 224;
+        executable[section + 20..section + 24].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(extract_icon(&executable, &Selector::Index(0)).is_err());
+    }
+}
@@ -0,0 +1,69 @@
+use crate::ResError;
+use crate::image::Image;
+use resvg::{tiny_skia, usvg};
+
+/// Parsed SVG document which can be rendered at any size.
+pub struct Svg {
+    tree: usvg::Tree,
+}
+
+impl Svg {
+    /// Parses an SVG document. Text must be converted to paths, as no fonts are loaded.
+    pub fn parse(data: &[u8]) -> Result<Self, ResError> {
+        let tree = usvg::Tree::from_data(data, &usvg::Options::default())
+            .map_err(|e| ResError::Parse(format!("SVG document: {e}")))?;
+        Ok(Self { tree })
+    }
+
+    /// Renders the document scaled to fit a square image, centered.
+    pub fn render(&self, size: u32) -> Result<Image, ResError> {
+        let mut pixmap = tiny_skia::Pixmap::new(size, size)
+            .ok_or(ResError::Custom("Invalid SVG rendering size!"))?;
+
+        let tree_size = self.tree.size();
+        let scale = (size as f32 / tree_size.width()).min(size as f32 / tree_size.height());
+        let transform = tiny_skia::Transform::from_scale(scale, scale).post_translate(
+            (size as f32 - tree_size.width() * scale) / 2.0,
+            (size as f32 - tree_size.height() * scale) / 2.0,
+        );
+        resvg::render(&self.tree, transform, &mut pixmap.as_mut());
+
+        let pixels = pixmap
+            .pixels()
+            .iter()
+            .flat_map(|pixel| {
+                let color = pixel.demultiply();
+                [color.red(), color.green(), color.blue(), color.alpha()]
+            })
+            .collect();
+
+        Ok(Image {
+            width: size,
+            height: size,
+            pixels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render() {
+        // Wide document with the left half red
+        let svg = Svg::parse(
+            br#"<svg xmlns="http://www.w3.org/2000/svg" width="40" height="20">
+                <rect width="20" height="20" fill="red"/>
+            </svg>"#,
+        )
+        .unwrap();
+
+        let image = svg.render(16).unwrap();
+        assert_eq!(image.pixels.len(), 16 * 16 * 4);
+        // Centered vertically, the top rows are transparent
+        assert_eq!(image.pixels[3], 0);
+        assert_eq!(&image.pixels[8 * 16 * 4..][..4], &[255, 0, 0, 255]);
+        assert_eq!(image.pixels[(8 * 16 + 15) * 4 + 3], 0);
+    }
+}
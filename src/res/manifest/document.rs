@@ -0,0 +1,198 @@
+/// XML namespaces of the manifest elements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Namespace {
+    AsmV1,
+    AsmV3,
+    WindowsSettings2005,
+    WindowsSettings2016,
+}
+
+impl Namespace {
+    fn uri(self) -> &'static str {
+        match self {
+            Self::AsmV1 => "urn:schemas-microsoft-com:asm.v1",
+            Self::AsmV3 => "urn:schemas-microsoft-com:asm.v3",
+            Self::WindowsSettings2005 => "http://schemas.microsoft.com/SMI/2005/WindowsSettings",
+            Self::WindowsSettings2016 => "http://schemas.microsoft.com/SMI/2016/WindowsSettings",
+        }
+    }
+}
+
+/// Element of a manifest document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Element {
+    namespace: Namespace,
+    name: &'static str,
+    attributes: Vec<(&'static str, String)>,
+    text: Option<String>,
+    children: Vec<Element>,
+}
+
+impl Element {
+    pub fn new(namespace: Namespace, name: &'static str) -> Self {
+        Self {
+            namespace,
+            name,
+            attributes: Vec::new(),
+            text: None,
+            children: Vec::new(),
+        }
+    }
+
+    pub fn with_attribute<T: Into<String>>(mut self, name: &'static str, value: T) -> Self {
+        self.attributes.push((name, value.into()));
+        self
+    }
+
+    pub fn with_text<T: Into<String>>(mut self, text: T) -> Self {
+        self.text = Some(text.into());
+        self
+    }
+
+    pub fn with_child(mut self, child: Element) -> Self {
+        self.children.push(child);
+        self
+    }
+
+    /// Child element with the name, created if missing.
+    pub fn child(&mut self, namespace: Namespace, name: &'static str) -> &mut Element {
+        let index = match self.position(namespace, name) {
+            Some(index) => index,
+            None => {
+                self.children.push(Element::new(namespace, name));
+                self.children.len() - 1
+            }
+        };
+        &mut self.children[index]
+    }
+
+    /// Adds a setting, replacing the previous one with the same name.
+    pub fn set(&mut self, child: Element) {
+        match self.position(child.namespace, child.name) {
+            Some(index) => self.children[index] = child,
+            None => self.children.push(child),
+        }
+    }
+
+    /// Adds a child element unless an identical one already exists.
+    pub fn add(&mut self, child: Element) {
+        if !self.children.contains(&child) {
+            self.children.push(child);
+        }
+    }
+
+    fn position(&self, namespace: Namespace, name: &str) -> Option<usize> {
+        self.children
+            .iter()
+            .position(|c| c.namespace == namespace && c.name == name)
+    }
+
+    /// Writes the element, declaring its namespace if it differs from the parent's.
+    fn write(&self, output: &mut String, parent: Option<Namespace>, level: usize) {
+        let indent = "    ".repeat(level);
+        output.push_str(&indent);
+        output.push('<');
+        output.push_str(self.name);
+        if parent != Some(self.namespace) {
+            push_attribute(output, "xmlns", self.namespace.uri());
+        }
+        for (name, value) in &self.attributes {
+            push_attribute(output, name, value);
+        }
+
+        if let Some(text) = &self.text {
+            output.push('>');
+            output.push_str(&escape(text));
+        } else if self.children.is_empty() {
+            output.push_str("/>\n");
+            return;
+        } else {
+            output.push_str(">\n");
+            for child in &self.children {
+                child.write(output, Some(self.namespace), level + 1);
+            }
+            output.push_str(&indent);
+        }
+
+        output.push_str("</");
+        output.push_str(self.name);
+        output.push_str(">\n");
+    }
+}
+
+/// Application manifest document built from features.
+///
+/// Features are merged into shared sections, which are serialized in a fixed order.
+#[derive(Debug)]
+pub(crate) struct Document {
+    root: Element,
+}
+
+impl Document {
+    pub fn new() -> Self {
+        Self {
+            root: Element::new(Namespace::AsmV1, "assembly")
+                .with_attribute("manifestVersion", "1.0"),
+        }
+    }
+
+    /// Adds a dependent assembly unless the same one was already added.
+    pub fn dependency(&mut self, identity: Element) {
+        let dependency = Element::new(Namespace::AsmV1, "dependency")
+            .with_child(Element::new(Namespace::AsmV1, "dependentAssembly").with_child(identity));
+        self.root.add(dependency);
+    }
+
+    /// The `application/windowsSettings` section.
+    pub fn windows_settings(&mut self) -> &mut Element {
+        self.root
+            .child(Namespace::AsmV3, "application")
+            .child(Namespace::AsmV3, "windowsSettings")
+    }
+
+    /// The `trustInfo/security/requestedPrivileges` section.
+    pub fn requested_privileges(&mut self) -> &mut Element {
+        self.root
+            .child(Namespace::AsmV3, "trustInfo")
+            .child(Namespace::AsmV3, "security")
+            .child(Namespace::AsmV3, "requestedPrivileges")
+    }
+
+    pub fn to_xml(&self) -> String {
+        let mut output = String::with_capacity(1024);
+        output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n");
+
+        let mut root = self.root.clone();
+        root.children.sort_by_key(|child| match child.name {
+            "dependency" => 0,
+            "trustInfo" => 1,
+            "compatibility" => 2,
+            _ => 3,
+        });
+        root.write(&mut output, None, 0);
+        output
+    }
+}
+
+fn push_attribute(output: &mut String, name: &str, value: &str) {
+    output.push(' ');
+    output.push_str(name);
+    output.push_str("=\"");
+    output.push_str(&escape(value));
+    output.push('"');
+}
+
+/// Escapes text for use in XML content and attribute values.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
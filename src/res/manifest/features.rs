@@ -1,16 +1,52 @@
+use super::document::{Document, Element, Namespace};
+
 /// Composable application manifest features.
 pub enum Feature {
     /// Sets the process DPI awareness mode.
     DpiAware(DpiMode),
     /// Use Common Control DLL (ComCtl32.dll) version 6.
     ControlsV6,
+    /// Sets the privileges the application requests from User Account Control.
+    ///
+    /// Also disables the installer detection, which would elevate executables named
+    /// like "setup" or "update".
+    ///
+    /// More info: <https://learn.microsoft.com/windows/security/application-security/application-control/user-account-control/how-it-works>
+    ExecutionLevel {
+        level: ExecutionLevel,
+        /// Allows bypassing the UI restrictions to drive the input of higher privilege
+        /// windows. Requires a signed executable in a secure location.
+        ui_access: bool,
+    },
 }
 
 impl Feature {
+    /// Manifest document with only this feature.
+    #[deprecated(note = "use `Manifest::from(feature)` to compose the manifest")]
     pub fn xml(&self) -> String {
+        let mut document = Document::new();
+        self.apply(&mut document);
+        document.to_xml()
+    }
+
+    /// Adds the elements of the feature to the document.
+    pub(crate) fn apply(&self, document: &mut Document) {
         match self {
-            Self::DpiAware(mode) => mode.xml(),
-            Self::ControlsV6 => include_str!("../../../manifest/controlsv6.xml").to_string(),
+            Self::DpiAware(mode) => mode.apply(document),
+            Self::ControlsV6 => document.dependency(
+                Element::new(Namespace::AsmV1, "assemblyIdentity")
+                    .with_attribute("type", "win32")
+                    .with_attribute("name", "Microsoft.Windows.Common-Controls")
+                    .with_attribute("version", "6.0.0.0")
+                    .with_attribute("processorArchitecture", "*")
+                    .with_attribute("publicKeyToken", "6595b64144ccf1df")
+                    .with_attribute("language", "*"),
+            ),
+            Self::ExecutionLevel { level, ui_access } => document.requested_privileges().set(
+                Element::new(Namespace::AsmV3, "requestedExecutionLevel")
+                    .with_attribute("level", level.value())
+                    .with_attribute("uiAccess", if *ui_access { "true" } else { "false" }),
+            ),
         }
     }
 }
@@ -27,7 +63,15 @@ pub enum DpiMode {
 }
 
 impl DpiMode {
+    /// Manifest document with only this DPI awareness mode.
+    #[deprecated(note = "use `Manifest::from(Feature::DpiAware(mode))` to compose the manifest")]
     pub fn xml(&self) -> String {
+        let mut document = Document::new();
+        self.apply(&mut document);
+        document.to_xml()
+    }
+
+    fn apply(&self, document: &mut Document) {
         let (aware, awareness) = match self {
             Self::Unaware => ("false", "unaware"),
             Self::System => ("true", "system"),
@@ -35,16 +79,32 @@ impl DpiMode {
             Self::PerMonitorV2 => ("true", "PerMonitorV2"), // Does `aware` should be "true/pm" or just "true"?
         };
 
-        let mut xml = String::from(include_str!("../../../manifest/dpiaware.xml"));
-        replace(&mut xml, "{aware}", aware);
-        replace(&mut xml, "{awareness}", awareness);
-        xml
+        let settings = document.windows_settings();
+        settings.set(Element::new(Namespace::WindowsSettings2005, "dpiAware").with_text(aware));
+        settings
+            .set(Element::new(Namespace::WindowsSettings2016, "dpiAwareness").with_text(awareness));
     }
 }
 
-fn replace(string: &mut String, key: &str, value: &str) {
-    let index = string.find(key).unwrap();
-    string.replace_range(index..index + key.len(), value);
+/// Privilege level requested by the application.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionLevel {
+    /// Runs with the same privileges as the parent process.
+    AsInvoker,
+    /// Runs with the highest privileges the user can obtain, prompting administrators.
+    HighestAvailable,
+    /// Runs only as an administrator, prompting for elevation.
+    RequireAdministrator,
+}
+
+impl ExecutionLevel {
+    fn value(self) -> &'static str {
+        match self {
+            Self::AsInvoker => "asInvoker",
+            Self::HighestAvailable => "highestAvailable",
+            Self::RequireAdministrator => "requireAdministrator",
+        }
+    }
 }
 
 #[cfg(test)]
@@ -52,8 +112,15 @@ mod tests {
     use super::*;
 
     #[test]
-    fn dpimode() {
-        // Checks the replace calls inside DpiMode::xml()
-        assert!(!DpiMode::System.xml().is_empty());
+    #[allow(deprecated)]
+    fn single_feature() {
+        let xml = DpiMode::System.xml();
+        assert!(xml.contains(">system</dpiAwareness>"));
+        assert_eq!(Feature::DpiAware(DpiMode::System).xml(), xml);
+        assert!(
+            Feature::ControlsV6
+                .xml()
+                .contains("Microsoft.Windows.Common-Controls")
+        );
     }
 }
@@ -1,9 +1,11 @@
 //! Application manifest resource. ([`RT_MANIFEST`](crate::manifest::Manifest))
 
+mod document;
 mod features;
 
 pub use features::*;
 
+use crate::custom::ResType;
 use crate::{ResError, ResWriter, Resource, util};
 use std::path::{Path, PathBuf};
 
@@ -18,12 +20,16 @@ use std::path::{Path, PathBuf};
 ///
 /// ### Example:
 /// ```
-/// # use winscribe::manifest::{Manifest, Feature, DpiMode};
+/// # use winscribe::manifest::{Manifest, Feature, DpiMode, ExecutionLevel};
 /// # use std::path::Path;
 /// // From features:
 /// Manifest::from([
 ///     Feature::DpiAware(DpiMode::PerMonitorV2),
 ///     Feature::ControlsV6,
+///     Feature::ExecutionLevel {
+///         level: ExecutionLevel::AsInvoker,
+///         ui_access: false,
+///     },
 /// ]);
 /// // From path:
 /// Manifest::from(Path::new("my_manifest.xml"));
@@ -49,6 +55,7 @@ impl Resource for Manifest {
 
 fn write_manifest<P: AsRef<Path>>(writer: &mut ResWriter, path: P) -> Result<(), ResError> {
     let escaped_path = util::escape_path(path)?;
+    writer.define(ResType::MANIFEST, 1, None)?;
     writer.line(format!("1 24 \"{escaped_path}\""));
     Ok(())
 }
@@ -61,21 +68,11 @@ impl From<String> for Manifest {
 
 impl From<&[Feature]> for Manifest {
     fn from(value: &[Feature]) -> Self {
-        let mut buffer = String::with_capacity(1024);
-
-        buffer.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n");
-        buffer.push_str(
-            "<assembly xmlns=\"urn:schemas-microsoft-com:asm.v1\" manifestVersion=\"1.0\">\n",
-        );
-
+        let mut document = document::Document::new();
         for feature in value {
-            buffer.push_str(&feature.xml());
-            buffer.push('\n');
+            feature.apply(&mut document);
         }
-
-        buffer.push_str("</assembly>");
-
-        Self::Internal(buffer)
+        Self::Internal(document.to_xml())
     }
 }
 
@@ -108,3 +105,50 @@ impl From<&Path> for Manifest {
         Self::External(value.to_path_buf())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merged_features() {
+        let Manifest::Internal(xml) = Manifest::from([
+            Feature::DpiAware(DpiMode::System),
+            Feature::ControlsV6,
+            Feature::DpiAware(DpiMode::PerMonitorV2),
+            Feature::ControlsV6,
+            Feature::ExecutionLevel {
+                level: ExecutionLevel::RequireAdministrator,
+                ui_access: false,
+            },
+        ]) else {
+            unreachable!()
+        };
+
+        assert_eq!(
+            xml,
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<assembly xmlns="urn:schemas-microsoft-com:asm.v1" manifestVersion="1.0">
+    <dependency>
+        <dependentAssembly>
+            <assemblyIdentity type="win32" name="Microsoft.Windows.Common-Controls" version="6.0.0.0" processorArchitecture="*" publicKeyToken="6595b64144ccf1df" language="*"/>
+        </dependentAssembly>
+    </dependency>
+    <trustInfo xmlns="urn:schemas-microsoft-com:asm.v3">
+        <security>
+            <requestedPrivileges>
+                <requestedExecutionLevel level="requireAdministrator" uiAccess="false"/>
+            </requestedPrivileges>
+        </security>
+    </trustInfo>
+    <application xmlns="urn:schemas-microsoft-com:asm.v3">
+        <windowsSettings>
+            <dpiAware xmlns="http://schemas.microsoft.com/SMI/2005/WindowsSettings">true</dpiAware>
+            <dpiAwareness xmlns="http://schemas.microsoft.com/SMI/2016/WindowsSettings">PerMonitorV2</dpiAwareness>
+        </windowsSettings>
+    </application>
+</assembly>
+"#
+        );
+    }
+}
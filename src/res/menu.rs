@@ -0,0 +1,323 @@
+//! Menu resource. ([`MENUEX`](crate::menu::Menu))
+
+use crate::custom::ResType;
+use crate::{ResError, ResId, ResWriter, Resource, util};
+
+const MFT_SEPARATOR: u32 = 0x0800;
+const MFS_GRAYED: u32 = 0x0003;
+const MFS_CHECKED: u32 = 0x0008;
+const MFS_DEFAULT: u32 = 0x1000;
+
+/// Menu resource. (`MENUEX` or `MENU`)
+///
+/// Can be loaded at runtime with `LoadMenuW` and used as a window menu or with
+/// `TrackPopupMenu` as a context menu.
+///
+/// ### Example:
+/// ```
+/// # use winscribe::menu::{Menu, MenuItem};
+/// const ID_OPEN: u16 = 101;
+/// const ID_EXIT: u16 = 102;
+///
+/// Menu::new("MAIN_MENU").with_item(MenuItem::popup(
+///     "&File",
+///     [
+///         MenuItem::command(ID_OPEN, "&Open...").with_shortcut("Ctrl+O"),
+///         MenuItem::separator(),
+///         MenuItem::command(ID_EXIT, "E&xit"),
+///     ],
+/// ));
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/menuex-resource>
+#[derive(Debug, Clone)]
+pub struct Menu {
+    id: ResId,
+    language: Option<u16>,
+    /// Syntax the menu is written in.
+    pub format: MenuFormat,
+    /// Top-level items of the menu.
+    pub items: Vec<MenuItem>,
+}
+
+impl Menu {
+    /// Creates a new, empty `MENUEX` resource.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    pub fn new<N: Into<ResId>>(id: N) -> Self {
+        Self {
+            id: id.into(),
+            language: None,
+            format: MenuFormat::Extended,
+            items: Vec::new(),
+        }
+    }
+
+    /// Sets the syntax the menu is written in.
+    pub fn with_format(mut self, format: MenuFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Adds a top-level item to the menu.
+    pub fn with_item(mut self, item: MenuItem) -> Self {
+        self.items.push(item);
+        self
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+}
+
+impl Resource for Menu {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        writer.define(ResType::MENU, self.id.clone(), self.language)?;
+        let mut commands = Vec::new();
+        collect_commands(&self.items, &mut commands);
+        writer.commands(&self.id, "Menu", "Command", commands, self.language)?;
+        writer.line(format!("{} {}", self.id, self.format.keyword()));
+        write_items(writer, self.format, &self.items);
+        Ok(())
+    }
+}
+
+/// Text and id of the command items including the ones in submenus.
+fn collect_commands<'a>(items: &'a [MenuItem], commands: &mut Vec<(&'a str, u16)>) {
+    for item in items {
+        match &item.kind {
+            MenuItemKind::Command(id) => commands.push((&item.text, *id)),
+            MenuItemKind::Popup(items) => collect_commands(items, commands),
+            MenuItemKind::Separator => (),
+        }
+    }
+}
+
+fn write_items(writer: &mut ResWriter, format: MenuFormat, items: &[MenuItem]) {
+    writer.begin();
+    for item in items {
+        item.write(writer, format);
+    }
+    writer.end();
+}
+
+/// Syntax of a menu resource.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MenuFormat {
+    /// Extended menu. (`MENUEX`)
+    #[default]
+    Extended,
+    /// Legacy menu for compatibility. (`MENU`)
+    ///
+    /// Does not support default items.
+    Legacy,
+}
+
+impl MenuFormat {
+    const fn keyword(&self) -> &'static str {
+        match self {
+            Self::Extended => "MENUEX",
+            Self::Legacy => "MENU",
+        }
+    }
+}
+
+/// An item of a [`Menu`].
+#[derive(Debug, Clone)]
+pub struct MenuItem {
+    kind: MenuItemKind,
+    text: String,
+    shortcut: Option<String>,
+    checked: bool,
+    grayed: bool,
+    default: bool,
+}
+
+#[derive(Debug, Clone)]
+enum MenuItemKind {
+    Command(u16),
+    Popup(Vec<MenuItem>),
+    Separator,
+}
+
+impl MenuItem {
+    fn new<T: Into<String>>(kind: MenuItemKind, text: T) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+            shortcut: None,
+            checked: false,
+            grayed: false,
+            default: false,
+        }
+    }
+
+    /// Item which sends a `WM_COMMAND` message with the `id` when selected.
+    ///
+    /// Use `&` in the `text` to mark the mnemonic character.
+    pub fn command<T: Into<String>>(id: u16, text: T) -> Self {
+        Self::new(MenuItemKind::Command(id), text)
+    }
+
+    /// Item which opens a submenu.
+    pub fn popup<T, I>(text: T, items: I) -> Self
+    where
+        T: Into<String>,
+        I: IntoIterator<Item = MenuItem>,
+    {
+        Self::new(MenuItemKind::Popup(items.into_iter().collect()), text)
+    }
+
+    /// Horizontal dividing line.
+    pub fn separator() -> Self {
+        Self::new(MenuItemKind::Separator, "")
+    }
+
+    /// Sets the keyboard shortcut text displayed aligned right. (ex: `Ctrl+O`)
+    ///
+    /// It is only a hint for the user, the shortcut needs to be handled separately.
+    pub fn with_shortcut<T: Into<String>>(mut self, shortcut: T) -> Self {
+        self.shortcut = Some(shortcut.into());
+        self
+    }
+
+    /// Displays a check mark next to the item.
+    pub fn checked(mut self) -> Self {
+        self.checked = true;
+        self
+    }
+
+    /// Disables the item and displays it grayed out.
+    pub fn grayed(mut self) -> Self {
+        self.grayed = true;
+        self
+    }
+
+    /// Displays the item in bold as the default item of the menu.
+    pub fn default_item(mut self) -> Self {
+        self.default = true;
+        self
+    }
+
+    /// Text of the item including the shortcut.
+    fn full_text(&self) -> String {
+        let text = match &self.shortcut {
+            Some(shortcut) => format!("{}\t{shortcut}", self.text),
+            None => self.text.clone(),
+        };
+        util::escape(&text)
+    }
+
+    fn state(&self) -> u32 {
+        let mut state = 0;
+        if self.checked {
+            state |= MFS_CHECKED;
+        }
+        if self.grayed {
+            state |= MFS_GRAYED;
+        }
+        if self.default {
+            state |= MFS_DEFAULT;
+        }
+        state
+    }
+
+    fn options(&self) -> String {
+        let mut options = String::new();
+        if self.checked {
+            options.push_str(", CHECKED");
+        }
+        if self.grayed {
+            options.push_str(", GRAYED");
+        }
+        options
+    }
+
+    fn write(&self, writer: &mut ResWriter, format: MenuFormat) {
+        match (&self.kind, format) {
+            (MenuItemKind::Command(id), MenuFormat::Extended) => writer.line(format!(
+                "MENUITEM \"{}\", {id}, 0, {:#X}",
+                self.full_text(),
+                self.state()
+            )),
+            (MenuItemKind::Command(id), MenuFormat::Legacy) => writer.line(format!(
+                "MENUITEM \"{}\", {id}{}",
+                self.full_text(),
+                self.options()
+            )),
+            (MenuItemKind::Separator, MenuFormat::Extended) => {
+                writer.line(format!("MENUITEM \"\", 0, {MFT_SEPARATOR:#X}, 0"))
+            }
+            (MenuItemKind::Separator, MenuFormat::Legacy) => writer.line("MENUITEM SEPARATOR"),
+            (MenuItemKind::Popup(items), MenuFormat::Extended) => {
+                writer.line(format!(
+                    "POPUP \"{}\", 0, 0, {:#X}",
+                    self.full_text(),
+                    self.state()
+                ));
+                write_items(writer, format, items);
+            }
+            (MenuItemKind::Popup(items), MenuFormat::Legacy) => {
+                writer.line(format!("POPUP \"{}\"{}", self.full_text(), self.options()));
+                write_items(writer, format, items);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output(format: MenuFormat) -> String {
+        let menu = Menu::new("MAIN")
+            .with_format(format)
+            .with_item(MenuItem::popup(
+                "&File",
+                [
+                    MenuItem::command(101, "&Open").with_shortcut("Ctrl+O"),
+                    MenuItem::command(102, "&Wrap").checked(),
+                    MenuItem::command(103, "&Save").grayed(),
+                    MenuItem::separator(),
+                    MenuItem::popup(
+                        "&Recent",
+                        [MenuItem::command(104, "None").checked().grayed()],
+                    ),
+                ],
+            ));
+
+        let mut writer = ResWriter::new();
+        menu.write(&mut writer).unwrap();
+        String::from_utf8_lossy(writer.as_bytes()).into_owned()
+    }
+
+    #[test]
+    fn menu_text() {
+        // Grayed items have the state 0x3, which includes MFS_DISABLED
+        assert!(output(MenuFormat::Extended).ends_with(
+            "MAIN MENUEX\n{\n\
+             \tPOPUP \"&File\", 0, 0, 0x0\n\t{\n\
+             \t\tMENUITEM \"&Open\\tCtrl+O\", 101, 0, 0x0\n\
+             \t\tMENUITEM \"&Wrap\", 102, 0, 0x8\n\
+             \t\tMENUITEM \"&Save\", 103, 0, 0x3\n\
+             \t\tMENUITEM \"\", 0, 0x800, 0\n\
+             \t\tPOPUP \"&Recent\", 0, 0, 0x0\n\t\t{\n\
+             \t\t\tMENUITEM \"None\", 104, 0, 0xB\n\t\t}\n\
+             \t}\n}\n"
+        ));
+
+        assert!(output(MenuFormat::Legacy).ends_with(
+            "MAIN MENU\n{\n\
+             \tPOPUP \"&File\"\n\t{\n\
+             \t\tMENUITEM \"&Open\\tCtrl+O\", 101\n\
+             \t\tMENUITEM \"&Wrap\", 102, CHECKED\n\
+             \t\tMENUITEM \"&Save\", 103, GRAYED\n\
+             \t\tMENUITEM SEPARATOR\n\
+             \t\tPOPUP \"&Recent\"\n\t\t{\n\
+             \t\t\tMENUITEM \"None\", 104, CHECKED, GRAYED\n\t\t}\n\
+             \t}\n}\n"
+        ));
+    }
+}
@@ -0,0 +1,275 @@
+//! Parser of message text (`.mc`) files.
+//!
+//! More info: <https://learn.microsoft.com/windows/win32/eventlog/message-text-files>
+
+use super::{Message, MessageTable, Severity};
+use crate::ResError;
+
+/// Name with its value from the `SeverityNames`, `FacilityNames` or `LanguageNames` lists.
+struct Named {
+    name: String,
+    value: u16,
+}
+
+struct Parser {
+    severities: Vec<Named>,
+    facilities: Vec<Named>,
+    languages: Vec<Named>,
+}
+
+impl Parser {
+    fn new() -> Self {
+        let named = |list: &[(&str, u16)]| {
+            list.iter()
+                .map(|(name, value)| Named {
+                    name: name.to_string(),
+                    value: *value,
+                })
+                .collect()
+        };
+
+        Self {
+            severities: named(&[
+                ("Success", 0x0),
+                ("Informational", 0x1),
+                ("Warning", 0x2),
+                ("Error", 0x3),
+            ]),
+            facilities: named(&[("System", 0x0FF), ("Application", 0xFFF)]),
+            languages: named(&[("English", 0x0409)]),
+        }
+    }
+
+    fn lookup(list: &[Named], name: &str, line: usize) -> Result<u16, ResError> {
+        list.iter()
+            .find(|named| named.name.eq_ignore_ascii_case(name))
+            .map(|named| named.value)
+            .ok_or_else(|| error(line, &format!("unknown name \"{name}\"")))
+    }
+}
+
+pub(super) fn parse(source: &str) -> Result<MessageTable, ResError> {
+    let mut parser = Parser::new();
+    let mut table = MessageTable::new();
+    let mut languages_declared = false;
+
+    // Values carried over from the previous message
+    let mut next_code: u32 = 0;
+    let mut severity = Severity::Success;
+    let mut facility = 0;
+
+    let mut current: Option<Message> = None;
+    let mut lines = source.lines().enumerate().map(|(i, line)| (i + 1, line));
+
+    while let Some((number, line)) = lines.next() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(';') {
+            continue;
+        }
+
+        let (key, value) = line
+            .split_once('=')
+            .map(|(key, value)| (key.trim(), value.trim()))
+            .ok_or_else(|| error(number, "expected a keyword"))?;
+
+        match key.to_ascii_lowercase().as_str() {
+            "severitynames" | "facilitynames" | "languagenames" => {
+                let mut list = value.to_string();
+                while !list.contains(')') {
+                    let (_, next) = lines
+                        .next()
+                        .ok_or_else(|| error(number, "unterminated name list"))?;
+                    list.push(' ');
+                    list.push_str(next.trim());
+                }
+
+                let names = parse_names(&list, number)?;
+                match key.to_ascii_lowercase().as_str() {
+                    "severitynames" => parser.severities = names,
+                    "facilitynames" => parser.facilities.extend(names),
+                    _ => {
+                        if !languages_declared {
+                            parser.languages.clear();
+                            languages_declared = true;
+                        }
+                        parser.languages.extend(names);
+                    }
+                }
+            }
+            "messageid" => {
+                if let Some(message) = current.take() {
+                    table.messages.push(message);
+                }
+
+                next_code = if value.is_empty() {
+                    next_code
+                } else if let Some(relative) = value.strip_prefix('+') {
+                    table
+                        .messages
+                        .last()
+                        .map_or(0, |m| u32::from(m.code))
+                        .saturating_add(parse_number(relative, number)?)
+                } else {
+                    parse_number(value, number)?
+                };
+
+                let code = u16::try_from(next_code)
+                    .map_err(|_| error(number, "message id must fit in 16 bits"))?;
+                next_code += 1;
+
+                let mut message = Message::new(code, "");
+                message.severity = severity;
+                message.facility = facility;
+                current = Some(message);
+            }
+            "severity" => {
+                let value = Parser::lookup(&parser.severities, value, number)?;
+                severity = match value {
+                    0x0 => Severity::Success,
+                    0x1 => Severity::Informational,
+                    0x2 => Severity::Warning,
+                    _ => Severity::Error,
+                };
+                message_mut(&mut current, number)?.severity = severity;
+            }
+            "facility" => {
+                facility = Parser::lookup(&parser.facilities, value, number)?;
+                message_mut(&mut current, number)?.facility = facility;
+            }
+            "symbolicname" => {
+                message_mut(&mut current, number)?.name = Some(value.to_string());
+            }
+            "language" => {
+                let language = Parser::lookup(&parser.languages, value, number)?;
+
+                let mut text = String::new();
+                loop {
+                    let (_, line) = lines
+                        .next()
+                        .ok_or_else(|| error(number, "message text is not terminated by \".\""))?;
+                    if line.trim_end() == "." {
+                        break;
+                    }
+                    text.push_str(line);
+                    text.push_str("\r\n");
+                }
+
+                let message = message_mut(&mut current, number)?;
+                if language == parser.languages[0].value {
+                    message.text = text;
+                } else {
+                    message.translations.push((language, text));
+                }
+            }
+            // Options which only affect the generated header
+            "messageidtypedef" | "outputbase" => {}
+            _ => return Err(error(number, &format!("unknown keyword \"{key}\""))),
+        }
+    }
+
+    if let Some(message) = current.take() {
+        table.messages.push(message);
+    }
+
+    table.language = Some(parser.languages[0].value);
+    Ok(table)
+}
+
+/// Parses a name list like `(Error=0x3:STATUS_SEVERITY_ERROR Warning=0x2)`.
+fn parse_names(list: &str, line: usize) -> Result<Vec<Named>, ResError> {
+    let list = list
+        .trim()
+        .strip_prefix('(')
+        .and_then(|list| list.split_once(')'))
+        .map(|(list, _)| list)
+        .ok_or_else(|| error(line, "name list must be in parentheses"))?;
+
+    let mut names = Vec::new();
+    for entry in list.split_whitespace() {
+        let (name, value) = entry
+            .split_once('=')
+            .ok_or_else(|| error(line, "expected name=value"))?;
+        let value = value.split_once(':').map_or(value, |(value, _)| value);
+
+        names.push(Named {
+            name: name.to_string(),
+            value: u16::try_from(parse_number(value, line)?)
+                .map_err(|_| error(line, "value must fit in 16 bits"))?,
+        });
+    }
+
+    if names.is_empty() {
+        return Err(error(line, "empty name list"));
+    }
+    Ok(names)
+}
+
+fn parse_number(value: &str, line: usize) -> Result<u32, ResError> {
+    let value = value.trim();
+    let result = match value
+        .strip_prefix("0x")
+        .or_else(|| value.strip_prefix("0X"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => value.parse(),
+    };
+    result.map_err(|_| error(line, &format!("invalid number \"{value}\"")))
+}
+
+fn message_mut(current: &mut Option<Message>, line: usize) -> Result<&mut Message, ResError> {
+    current
+        .as_mut()
+        .ok_or_else(|| error(line, "expected MessageId first"))
+}
+
+fn error(line: usize, message: &str) -> ResError {
+    ResError::Parse(format!("message text file line {line}: {message}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mc() {
+        let table = parse(
+            "; // Header comment
+SeverityNames=(Success=0x0:STATUS_SEVERITY_SUCCESS
+               Error=0x3:STATUS_SEVERITY_ERROR
+              )
+FacilityNames=(Runtime=0x2:FACILITY_RUNTIME)
+LanguageNames=(English=0x409:MSG00409)
+LanguageNames=(German=0x407:MSG00407)
+
+MessageId=0x1
+Severity=Error
+Facility=Runtime
+SymbolicName=MSG_BAD_COMMAND
+Language=English
+You have chosen an incorrect command.
+.
+Language=German
+Sie haben einen falschen Befehl gewählt.
+.
+
+MessageId=
+SymbolicName=MSG_NEXT
+Language=English
+Next %1
+.
+",
+        )
+        .unwrap();
+
+        assert_eq!(table.language, Some(0x409));
+        assert_eq!(table.messages.len(), 2);
+        assert_eq!(table.messages[0].id().unwrap(), 0xC0020001);
+        assert_eq!(
+            table.messages[0].text,
+            "You have chosen an incorrect command.\r\n"
+        );
+        assert_eq!(table.messages[0].translations[0].0, 0x407);
+        assert_eq!(table.messages[1].id().unwrap(), 0xC0020002);
+        assert_eq!(table.messages[1].name.as_deref(), Some("MSG_NEXT"));
+    }
+}
@@ -0,0 +1,346 @@
+//! Message table resource. ([`MESSAGETABLE`](crate::message_table::MessageTable))
+
+mod mc;
+
+use crate::custom::ResType;
+use crate::util::{self, push_u16, push_u32};
+use crate::writer::DEFAULT_LANGUAGE;
+use crate::{ResError, ResId, ResWriter, Resource};
+use std::fmt::Write;
+use std::path::Path;
+
+/// Message table resource. (`MESSAGETABLE`)
+///
+/// Contains the messages used by `FormatMessageW` and Event Log sources. The binary
+/// `MESSAGE_RESOURCE_DATA` is built without the message compiler (`mc.exe`).
+///
+/// ### Example:
+/// ```
+/// # use winscribe::message_table::{Message, MessageTable, Severity};
+/// MessageTable::new()
+///     .with(
+///         Message::new(1, "Service started on port %1.")
+///             .with_name("MSG_STARTED")
+///             .with_severity(Severity::Informational)
+///             .with_translation(0x0407, "Dienst auf Port %1 gestartet."),
+///     )
+///     .with(Message::new(2, "Failed to open %1: %2").with_name("MSG_OPEN_FAILED"));
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/messagetable-resource>
+#[derive(Debug, Clone)]
+pub struct MessageTable {
+    /// Id of the resource. (`1` by default)
+    pub id: ResId,
+    /// Language of the messages' default text. (language of the builder by default)
+    pub language: Option<u16>,
+    /// Messages of the table.
+    pub messages: Vec<Message>,
+}
+
+impl MessageTable {
+    /// Creates a new, empty message table.
+    pub fn new() -> Self {
+        Self {
+            id: ResId::Int(1),
+            language: None,
+            messages: Vec::new(),
+        }
+    }
+
+    /// Parses a message text (`.mc`) file.
+    pub fn from_mc<P: AsRef<Path>>(path: P) -> Result<Self, ResError> {
+        mc::parse(&std::fs::read_to_string(path)?)
+    }
+
+    /// Sets the id of the resource.
+    ///
+    /// `FormatMessageW` and the event log only read the table with the id `1`.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    pub fn with_id<N: Into<ResId>>(mut self, id: N) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Sets the language of the messages' default text.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+
+    /// Adds a message to the table.
+    pub fn with(mut self, message: Message) -> Self {
+        self.messages.push(message);
+        self
+    }
+
+    /// Languages the table contains messages in. The default language is always first.
+    fn languages(&self, default: u16) -> Vec<u16> {
+        let mut languages = vec![default];
+        for message in &self.messages {
+            for (language, _) in &message.translations {
+                if !languages.contains(language) {
+                    languages.push(*language);
+                }
+            }
+        }
+        languages
+    }
+
+    /// Encodes the messages of the language as a binary `MESSAGE_RESOURCE_DATA`.
+    ///
+    /// Messages without a translation use their default text.
+    pub fn to_binary(&self, language: u16) -> Result<Vec<u8>, ResError> {
+        self.encode(language, self.language.unwrap_or(DEFAULT_LANGUAGE))
+    }
+
+    /// Encodes the messages of the language, with `default` as the language of the default text.
+    fn encode(&self, language: u16, default: u16) -> Result<Vec<u8>, ResError> {
+        let mut entries: Vec<(u32, &str)> = Vec::with_capacity(self.messages.len());
+        for message in &self.messages {
+            entries.push((message.id()?, message.text(language, default)));
+        }
+        entries.sort_by_key(|(id, _)| *id);
+
+        if entries.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(ResError::Custom(
+                "Message table contains duplicate message ids!",
+            ));
+        }
+
+        // Group consecutive ids into blocks of (low id, high id, first entry index)
+        let mut blocks: Vec<(u32, u32, usize)> = Vec::new();
+        for (index, (id, _)) in entries.iter().enumerate() {
+            match blocks.last_mut() {
+                Some(block) if block.1 + 1 == *id => block.1 = *id,
+                _ => blocks.push((*id, *id, index)),
+            }
+        }
+
+        let mut encoded = Vec::with_capacity(entries.len());
+        for (_, text) in &entries {
+            let mut entry = Vec::with_capacity(text.len() * 2 + 8);
+            push_u16(&mut entry, 0); // Length, set below
+            push_u16(&mut entry, 1); // MESSAGE_RESOURCE_UNICODE
+            util::push_utf16(&mut entry, text);
+            entry.resize(entry.len().next_multiple_of(4), 0);
+
+            let length = u16::try_from(entry.len())
+                .map_err(|_| ResError::Custom("Message text is too long!"))?;
+            entry[..2].copy_from_slice(&length.to_le_bytes());
+            encoded.push(entry);
+        }
+
+        let mut offsets = Vec::with_capacity(encoded.len());
+        let mut offset = 4 + blocks.len() * 12;
+        for entry in &encoded {
+            offsets.push(offset as u32);
+            offset += entry.len();
+        }
+
+        let mut data = Vec::with_capacity(offset);
+        push_u32(&mut data, blocks.len() as u32);
+        for (low, high, index) in blocks {
+            push_u32(&mut data, low);
+            push_u32(&mut data, high);
+            push_u32(&mut data, offsets[index]);
+        }
+        for entry in encoded {
+            data.extend(entry);
+        }
+
+        Ok(data)
+    }
+
+    /// Saves a C header with a `#define` for every named message.
+    pub fn save_header<P: AsRef<Path>>(&self, path: P) -> Result<(), ResError> {
+        let mut header = String::from("#pragma once\n\n");
+        for (name, id) in self.names()? {
+            writeln!(header, "#define {name} ((DWORD){id:#010X}L)").unwrap();
+        }
+        util::to_file(path, header.as_bytes())?;
+        Ok(())
+    }
+
+    /// Saves a Rust file with a constant for every named message. Meant to be used with `include!`.
+    pub fn save_rust<P: AsRef<Path>>(&self, path: P) -> Result<(), ResError> {
+        let mut source = String::new();
+        for (name, id) in self.names()? {
+            writeln!(
+                source,
+                "#[allow(dead_code)]\npub const {name}: u32 = {id:#010X};"
+            )
+            .unwrap();
+        }
+        util::to_file(path, source.as_bytes())?;
+        Ok(())
+    }
+
+    /// Validated name and id of the named messages.
+    fn names(&self) -> Result<Vec<(&str, u32)>, ResError> {
+        let mut names = Vec::new();
+        for message in &self.messages {
+            if let Some(name) = &message.name {
+                util::validate_name(name)?;
+                names.push((name.as_str(), message.id()?));
+            }
+        }
+        Ok(names)
+    }
+}
+
+impl Default for MessageTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resource for MessageTable {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        let default = self.language.unwrap_or(writer.default_language());
+        for language in self.languages(default) {
+            let path = util::hashed_out_file("msgtable", "bin", &self.encode(language, default)?)?;
+            writer.define(ResType::MESSAGETABLE, self.id.clone(), Some(language))?;
+            writer.line(format!(
+                "{} MESSAGETABLE \"{}\"",
+                self.id,
+                util::escape_path(path)?
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A message of a [`MessageTable`].
+///
+/// The text can contain inserts (`%1`, `%2`, ...) which are replaced by `FormatMessageW`.
+#[derive(Debug, Clone)]
+pub struct Message {
+    /// Code of the message. The lower 16 bits of the message id.
+    pub code: u16,
+    /// Severity of the message.
+    pub severity: Severity,
+    /// Facility code of the message. (12 bits)
+    pub facility: u16,
+    /// Sets the customer bit of the message id.
+    pub customer: bool,
+    /// Symbolic name used for the generated constants. (ex: `MSG_STARTED`)
+    pub name: Option<String>,
+    /// Text of the message in the table's language.
+    pub text: String,
+    /// Text of the message in other languages.
+    pub translations: Vec<(u16, String)>,
+}
+
+impl Message {
+    /// Creates a new message with success severity and facility 0.
+    pub fn new<T: Into<String>>(code: u16, text: T) -> Self {
+        Self {
+            code,
+            severity: Severity::Success,
+            facility: 0,
+            customer: false,
+            name: None,
+            text: text.into(),
+            translations: Vec::new(),
+        }
+    }
+
+    /// Sets the symbolic name used for the generated constants.
+    pub fn with_name<T: Into<String>>(mut self, name: T) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Sets the severity of the message.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = severity;
+        self
+    }
+
+    /// Sets the facility code of the message.
+    pub fn with_facility(mut self, facility: u16) -> Self {
+        self.facility = facility;
+        self
+    }
+
+    /// Adds the text of the message in another language.
+    pub fn with_translation<T: Into<String>>(mut self, language: u16, text: T) -> Self {
+        self.translations.push((language, text.into()));
+        self
+    }
+
+    /// Full 32-bit message id as used by `FormatMessageW` and `ReportEventW`.
+    pub fn id(&self) -> Result<u32, ResError> {
+        if self.facility > 0xFFF {
+            return Err(ResError::Custom(
+                "Message facility code must fit in 12 bits!",
+            ));
+        }
+
+        Ok((self.severity as u32) << 30
+            | u32::from(self.customer) << 29
+            | u32::from(self.facility) << 16
+            | u32::from(self.code))
+    }
+
+    fn text(&self, language: u16, default: u16) -> &str {
+        self.translations
+            .iter()
+            .find(|(lang, _)| *lang == language && language != default)
+            .map_or(&self.text, |(_, text)| text)
+    }
+}
+
+/// Severity of a [`Message`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Severity {
+    #[default]
+    Success = 0x0,
+    Informational = 0x1,
+    Warning = 0x2,
+    Error = 0x3,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary() {
+        let table = MessageTable::new()
+            .with(Message::new(1, "A"))
+            .with(Message::new(2, "BC"))
+            .with(Message::new(5, "D").with_severity(Severity::Error));
+        let data = table.to_binary(DEFAULT_LANGUAGE).unwrap();
+
+        // 2 blocks: [1, 2] and [0xC0000005]
+        assert_eq!(&data[..4], &2u32.to_le_bytes());
+        assert_eq!(&data[4..16], &[1, 0, 0, 0, 2, 0, 0, 0, 28, 0, 0, 0]);
+        assert_eq!(&data[16..24], &[5, 0, 0, 0xC0, 5, 0, 0, 0xC0]);
+        // "A\0" padded to 8 bytes with the header
+        assert_eq!(&data[28..36], &[8, 0, 1, 0, b'A', 0, 0, 0]);
+        assert_eq!(data.len(), 28 + 8 + 12 + 8);
+    }
+
+    #[test]
+    fn default_language() {
+        let table = MessageTable::new().with(Message::new(1, "A").with_translation(0x0409, "B"));
+        assert_eq!(table.languages(0x0407), [0x0407, 0x0409]);
+        assert_eq!(table.encode(0x0409, 0x0407).unwrap()[20], b'B');
+        // Without a builder, the default text is en-US
+        assert_eq!(table.to_binary(0x0409).unwrap()[20], b'A');
+    }
+
+    #[test]
+    fn names() {
+        let table = MessageTable::new()
+            .with(Message::new(1, "A").with_name("MSG_A"))
+            .with(Message::new(2, "B"));
+        assert_eq!(table.names().unwrap(), [("MSG_A", 1)]);
+
+        let table = table.with(Message::new(3, "C").with_name("MSG C"));
+        assert!(table.names().is_err());
+    }
+}
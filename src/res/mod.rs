@@ -1,3 +1,15 @@
+pub mod accelerators;
+pub mod animated;
+pub mod bitmap;
+pub mod custom;
+pub mod dialog;
+#[cfg(feature = "etw")]
+pub mod event_manifest;
+pub mod font;
+pub mod html;
 pub mod icon;
 pub mod manifest;
+pub mod menu;
+pub mod message_table;
+pub mod raw_data;
 pub mod version_info;
@@ -0,0 +1,63 @@
+//! Raw data resource. ([`RCDATA`](crate::raw_data::RawData))
+
+use crate::custom::ResType;
+use crate::{DataSource, ResError, ResId, ResWriter, Resource, util};
+
+/// Raw data resource. (`RCDATA`)
+///
+/// Embeds arbitrary bytes which can be located at runtime with `FindResourceW`.
+///
+/// ### Example:
+/// ```
+/// # use winscribe::raw_data::RawData;
+/// # use std::path::Path;
+/// // From path:
+/// RawData::new("DEFAULT_CONFIG", Path::new("config.toml"));
+/// // From memory:
+/// RawData::new(101, b"Hello World!");
+/// RawData::new(102, vec![0u8; 16]);
+/// ```
+///
+/// More info: <https://learn.microsoft.com/windows/win32/menurc/rcdata-resource>
+pub struct RawData {
+    id: ResId,
+    language: Option<u16>,
+    data: DataSource,
+}
+
+impl RawData {
+    /// Creates a new raw data resource.
+    ///
+    /// * `id`: A unique name or a 16-bit unsigned integer.
+    /// * `data`: Path to a file or the in-memory bytes.
+    pub fn new<N, D>(id: N, data: D) -> Self
+    where
+        N: Into<ResId>,
+        D: Into<DataSource>,
+    {
+        Self {
+            id: id.into(),
+            language: None,
+            data: data.into(),
+        }
+    }
+
+    /// Sets the Windows Language Code of the resource.
+    pub fn with_language(mut self, language: u16) -> Self {
+        self.language = Some(language);
+        self
+    }
+}
+
+impl Resource for RawData {
+    fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        let path = self.data.path("rcdata", "bin")?;
+        writer.define(ResType::RCDATA, self.id.clone(), self.language)?;
+        writer.line(format!(
+            "{} RCDATA \"{}\"",
+            self.id,
+            util::escape_path(path)?
+        ));
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ mod fixed;
 pub use block::*;
 pub use fixed::*;
 
+use crate::custom::ResType;
 use crate::{ResError, ResWriter, Resource};
 
 /// Version-information resource. (`VERSIONINFO`)
@@ -20,6 +21,8 @@ pub struct VersionInfo {
     pub fixed: FixedInfo,
     /// String and variable information block of the `VERSIONINFO` resource.
     pub block: BlockInfo,
+    /// Windows Language Code of the resource. Uses the builder's default when not set.
+    pub language: Option<u16>,
 }
 
 impl VersionInfo {
@@ -33,10 +36,11 @@ impl VersionInfo {
         Ok(Self {
             fixed: FixedInfo::from_env()?,
             block: BlockInfo::from_env()?,
+            language: None,
         })
     }
 
-    /// Sets the Windows Language Code of the resource.
+    /// Sets the Windows Language Code of the resource and its string information block.
     ///
     /// Some possible values:
     /// * Language Netural: `0x0000`
@@ -47,12 +51,14 @@ impl VersionInfo {
     /// <https://learn.microsoft.com/openspecs/windows_protocols/ms-lcid>
     pub fn with_language(mut self, language: u16) -> Self {
         self.block.language = language;
+        self.language = Some(language);
         self
     }
 }
 
 impl Resource for VersionInfo {
     fn write(&self, writer: &mut ResWriter) -> Result<(), ResError> {
+        writer.define(ResType::VERSION, 1, self.language)?;
         writer.line("1 VERSIONINFO");
         self.fixed.write(writer);
         self.block.write(writer);
@@ -0,0 +1,75 @@
+use crate::{ResError, util};
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+/// Source of a resource's binary data.
+///
+/// It can be construsted from:
+/// * Path to a file. (must be a [`PathBuf`](std::path::PathBuf) or [`&Path`](std::path::Path))
+/// * In-memory bytes as a [`Vec<u8>`] or a `&'static [u8]`. (ex: `include_bytes!(...)`)
+#[derive(Debug, Clone)]
+pub enum DataSource {
+    File(PathBuf),
+    Owned(Vec<u8>),
+    Static(&'static [u8]),
+}
+
+impl DataSource {
+    /// Path to a file containing the data.
+    ///
+    /// In-memory data is written to a uniquely named file in `OUT_DIR`.
+    pub(crate) fn path(&self, prefix: &str, ext: &str) -> Result<PathBuf, ResError> {
+        match self {
+            Self::File(path) => Ok(path.clone()),
+            Self::Owned(data) => util::hashed_out_file(prefix, ext, data),
+            Self::Static(data) => util::hashed_out_file(prefix, ext, data),
+        }
+    }
+
+    /// Content of the data, read from the file if necessary.
+    pub(crate) fn bytes(&self) -> Result<Cow<'_, [u8]>, ResError> {
+        match self {
+            Self::File(path) => Ok(Cow::Owned(std::fs::read(path)?)),
+            Self::Owned(data) => Ok(Cow::Borrowed(data)),
+            Self::Static(data) => Ok(Cow::Borrowed(data)),
+        }
+    }
+
+    /// Name of the source to be displayed in messages.
+    pub(crate) fn name(&self) -> String {
+        match self {
+            Self::File(path) => path.display().to_string(),
+            Self::Owned(_) | Self::Static(_) => "<memory>".to_string(),
+        }
+    }
+}
+
+impl From<PathBuf> for DataSource {
+    fn from(value: PathBuf) -> Self {
+        Self::File(value)
+    }
+}
+
+impl From<&Path> for DataSource {
+    fn from(value: &Path) -> Self {
+        Self::File(value.to_path_buf())
+    }
+}
+
+impl From<Vec<u8>> for DataSource {
+    fn from(value: Vec<u8>) -> Self {
+        Self::Owned(value)
+    }
+}
+
+impl From<&'static [u8]> for DataSource {
+    fn from(value: &'static [u8]) -> Self {
+        Self::Static(value)
+    }
+}
+
+impl<const N: usize> From<&'static [u8; N]> for DataSource {
+    fn from(value: &'static [u8; N]) -> Self {
+        Self::Static(value.as_slice())
+    }
+}
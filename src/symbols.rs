@@ -0,0 +1,531 @@
+use crate::custom::ResType;
+use crate::{ResError, ResId};
+use std::fmt::Write;
+
+/// Symbols of the resource ids and command ids defined in the resource file.
+#[derive(Debug, Default)]
+pub(crate) struct Symbols {
+    /// Constant name and id of the resources.
+    resources: Vec<(String, ResId)>,
+    /// Command id groups of menus and dialogs.
+    groups: Vec<Group>,
+    /// Constant name and index of the icons of an icon set.
+    icons: Vec<(String, usize)>,
+}
+
+#[derive(Debug)]
+struct Group {
+    /// Kind and normalized id of the resource the commands belong to.
+    kind: String,
+    owner: ResId,
+    name: String,
+    /// Whether the variants are named after the texts in the default language.
+    preferred: bool,
+    /// Variant name and the id of the commands.
+    commands: Vec<(String, u16)>,
+}
+
+impl Group {
+    /// Sorted ids of the commands.
+    fn ids(&self) -> Vec<u16> {
+        let mut ids: Vec<u16> = self.commands.iter().map(|(_, id)| *id).collect();
+        ids.sort_unstable();
+        ids
+    }
+}
+
+impl Symbols {
+    /// Adds a constant for the resource.
+    ///
+    /// Named resources are named after their name (ex: `APP_ICON`), numbered resources after
+    /// their type and number. (ex: `ICON_32512`)
+    pub fn resource(&mut self, kind: &ResType, id: &ResId) -> Result<(), ResError> {
+        let constant = match id {
+            ResId::Name(name) => constant_name(name),
+            ResId::Int(number) => format!("{}_{number}", type_name(kind)),
+        };
+        self.constant(constant, id.clone())
+    }
+
+    /// Adds a constant of a resource id.
+    ///
+    /// Returns an error if a constant with the same name but a different id was already added.
+    pub fn constant(&mut self, constant: String, id: ResId) -> Result<(), ResError> {
+        match self.resources.iter().find(|(c, _)| *c == constant) {
+            // Names are case-insensitive
+            Some((_, existing)) if existing.normalized() == id.normalized() => Ok(()),
+            Some((_, existing)) => Err(ResError::Duplicate(format!(
+                "Constant {constant} of the resources \"{existing}\" and \"{id}\""
+            ))),
+            None => {
+                self.resources.push((constant, id));
+                Ok(())
+            }
+        }
+    }
+
+    /// Adds an enum of the command ids.
+    ///
+    /// * `owner`: Id of the resource the commands belong to.
+    /// * `kind`: Kind of the owner, used for numeric ids. (ex: `Menu`)
+    /// * `suffix`: Suffix of the enum name. (ex: `Command`)
+    /// * `commands`: Text and id of each command.
+    /// * `preferred`: Whether the texts are in the default language. The variants are named
+    ///   after the first definition of the resource, or the one in the default language.
+    ///
+    /// Returns an error if another language of the resource has different command ids, or if
+    /// another resource has an enum with the same name.
+    pub fn commands<'a, I>(
+        &mut self,
+        owner: &ResId,
+        kind: &str,
+        suffix: &str,
+        commands: I,
+        preferred: bool,
+    ) -> Result<(), ResError>
+    where
+        I: IntoIterator<Item = (&'a str, u16)>,
+    {
+        let name = match owner {
+            ResId::Int(id) => format!("{kind}{id}{suffix}"),
+            ResId::Name(name) => format!("{}{suffix}", pascal_case(name)),
+        };
+
+        let mut group = Group {
+            kind: kind.to_string(),
+            owner: owner.normalized(),
+            name,
+            preferred,
+            commands: Vec::new(),
+        };
+        for (text, id) in commands {
+            // Discriminants must be unique
+            if group.commands.iter().any(|(_, i)| *i == id) {
+                continue;
+            }
+
+            let mut variant = pascal_case(text.split('\t').next().unwrap_or_default());
+            if variant == "Self" {
+                variant.insert_str(0, "Item");
+            }
+            if variant.is_empty()
+                || variant.starts_with(|c: char| c.is_ascii_digit())
+                || group.commands.iter().any(|(v, _)| *v == variant)
+            {
+                variant = format!("{variant}Id{id}");
+            }
+            group.commands.push((variant, id));
+        }
+
+        if group.commands.is_empty() {
+            return Ok(());
+        }
+        // Each language of a resource adds a group with translated texts
+        let same_owner = |g: &&mut Group| g.kind == group.kind && g.owner == group.owner;
+        if let Some(existing) = self.groups.iter_mut().find(same_owner) {
+            if existing.ids() != group.ids() {
+                return Err(ResError::Duplicate(format!(
+                    "Enum {} with different command ids in another language",
+                    group.name
+                )));
+            }
+            if group.preferred && !existing.preferred {
+                *existing = group;
+            }
+            return Ok(());
+        }
+        if self.groups.iter().any(|g| g.name == group.name) {
+            return Err(ResError::Duplicate(format!("Enum {}", group.name)));
+        }
+        self.groups.push(group);
+        Ok(())
+    }
+
+    /// Adds constants of the id and the index in the executable of an icon of an icon set.
+    pub fn icon_index(&mut self, name: &str, id: u16, index: usize) -> Result<(), ResError> {
+        let name = constant_name(name);
+        self.constant(format!("ICON_{name}"), ResId::Int(id))?;
+        self.icons.push((format!("ICON_INDEX_{name}"), index));
+        Ok(())
+    }
+
+    pub fn has_icon_indexes(&self) -> bool {
+        !self.icons.is_empty()
+    }
+
+    /// Rust module content, meant to be used with `include!`.
+    pub fn to_rust(&self) -> String {
+        let mut output = String::from("// Resource ids generated by winscribe.\n");
+
+        for (constant, id) in &self.resources {
+            match id {
+                ResId::Name(name) => {
+                    let wide: Vec<String> = name.encode_utf16().map(|c| c.to_string()).collect();
+                    let _ = write!(
+                        output,
+                        "\n/// Name of the `{name}` resource as a null-terminated UTF-16 string.\n\
+                         #[allow(dead_code)]\n\
+                         pub const {constant}: *const u16 = {{\n\
+                         \x20   const NAME: &[u16] = &[{}, 0];\n\
+                         \x20   NAME.as_ptr()\n\
+                         }};\n",
+                        wide.join(", ")
+                    );
+                }
+                ResId::Int(number) => {
+                    let _ = write!(
+                        output,
+                        "\n/// Id of a numbered resource. (use with `MAKEINTRESOURCEW`)\n\
+                         #[allow(dead_code)]\n\
+                         pub const {constant}: u16 = {number};\n"
+                    );
+                }
+            }
+        }
+
+        for (constant, index) in &self.icons {
+            let _ = write!(
+                output,
+                "\n/// Index of the icon in the executable. (ex: `DefaultIcon = app.exe,{index}`)\n\
+                 #[allow(dead_code)]\n\
+                 pub const {constant}: u32 = {index};\n"
+            );
+        }
+
+        for group in &self.groups {
+            let _ = write!(
+                output,
+                "\n#[allow(dead_code)]\n\
+                 #[repr(u16)]\n\
+                 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]\n\
+                 pub enum {} {{\n",
+                group.name
+            );
+            for (variant, id) in &group.commands {
+                let _ = writeln!(output, "    {variant} = {id},");
+            }
+            let _ = write!(
+                output,
+                "}}\n\n\
+                 #[allow(dead_code)]\n\
+                 impl {0} {{\n\
+                 \x20   /// Command id. (`LOWORD(wParam)` of `WM_COMMAND`)\n\
+                 \x20   pub const fn id(self) -> u16 {{\n\
+                 \x20       self as u16\n\
+                 \x20   }}\n\
+                 }}\n\n\
+                 impl TryFrom<u16> for {0} {{\n\
+                 \x20   type Error = u16;\n\n\
+                 \x20   fn try_from(id: u16) -> Result<Self, Self::Error> {{\n\
+                 \x20       match id {{\n",
+                group.name
+            );
+            for (variant, id) in &group.commands {
+                let _ = writeln!(output, "            {id} => Ok(Self::{variant}),");
+            }
+            output.push_str("            _ => Err(id),\n        }\n    }\n}\n");
+        }
+
+        output
+    }
+
+    /// C header content with a `#define` for each symbol.
+    ///
+    /// The resource names are hidden from the resource compiler, as it would replace the
+    /// names in the resource file.
+    pub fn to_c_header(&self) -> String {
+        let mut output = String::from("// Resource ids generated by winscribe.\n#pragma once\n");
+
+        let names: Vec<_> = self
+            .resources
+            .iter()
+            .filter_map(|(constant, id)| match id {
+                ResId::Name(name) => Some((constant, name)),
+                ResId::Int(_) => None,
+            })
+            .collect();
+        if !names.is_empty() {
+            output.push_str("\n#ifndef RC_INVOKED\n");
+            for (constant, name) in names {
+                let _ = writeln!(output, "#define {constant} L\"{name}\"");
+            }
+            output.push_str("#endif\n");
+        }
+
+        let numbers: Vec<_> = self
+            .resources
+            .iter()
+            .filter_map(|(constant, id)| match id {
+                ResId::Int(number) => Some((constant, number)),
+                ResId::Name(_) => None,
+            })
+            .collect();
+        if !numbers.is_empty() {
+            output.push('\n');
+            for (constant, number) in numbers {
+                let _ = writeln!(output, "#define {constant} {number}");
+            }
+        }
+
+        if !self.icons.is_empty() {
+            output.push('\n');
+            for (constant, index) in &self.icons {
+                let _ = writeln!(output, "#define {constant} {index}");
+            }
+        }
+
+        for group in &self.groups {
+            output.push('\n');
+            let prefix = constant_name(&snake_case(&group.name));
+            for (variant, id) in &group.commands {
+                let _ = writeln!(
+                    output,
+                    "#define {prefix}_{} {id}",
+                    constant_name(&snake_case(variant))
+                );
+            }
+        }
+
+        output
+    }
+}
+
+/// Name of the resource type used in the constants of numbered resources.
+fn type_name(kind: &ResType) -> String {
+    match kind {
+        ResType::Int(number) => match number {
+            1 => "CURSOR_IMAGE".to_string(),
+            2 => "BITMAP".to_string(),
+            3 => "ICON_IMAGE".to_string(),
+            4 => "MENU".to_string(),
+            5 => "DIALOG".to_string(),
+            6 => "STRING".to_string(),
+            7 => "FONTDIR".to_string(),
+            8 => "FONT".to_string(),
+            9 => "ACCELERATORS".to_string(),
+            10 => "RCDATA".to_string(),
+            11 => "MESSAGETABLE".to_string(),
+            12 => "CURSOR".to_string(),
+            14 => "ICON".to_string(),
+            16 => "VERSION".to_string(),
+            21 => "ANICURSOR".to_string(),
+            22 => "ANIICON".to_string(),
+            23 => "HTML".to_string(),
+            24 => "MANIFEST".to_string(),
+            _ => format!("TYPE{number}"),
+        },
+        ResType::Name(name) => constant_name(name),
+    }
+}
+
+/// Converts a resource name to an upper case constant name. (ex: `app.icon` to `APP_ICON`)
+fn constant_name(name: &str) -> String {
+    let constant: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if constant.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("_{constant}")
+    } else {
+        constant
+    }
+}
+
+/// Converts text to a type name. (ex: `Save &As...` to `SaveAs`)
+///
+/// Latin letters with diacritics are transliterated (ex: `Ö&ffnen` to `Oeffnen`), and text
+/// with any other non-ASCII letter or digit results in an empty name.
+fn pascal_case(text: &str) -> String {
+    let mut ascii = String::with_capacity(text.len());
+    for c in text.chars().filter(|c| *c != '&') {
+        if c.is_ascii() {
+            ascii.push(c);
+        } else if let Some(replacement) = transliterate(c) {
+            ascii.push_str(replacement);
+        } else if c.is_alphanumeric() {
+            return String::new();
+        } else {
+            ascii.push(' ');
+        }
+    }
+
+    ascii
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let (first, rest) = word.split_at(1);
+            first.to_ascii_uppercase() + &rest.to_ascii_lowercase()
+        })
+        .collect()
+}
+
+/// ASCII spelling of a Latin-1 letter with a diacritic. (ex: `é` to `e`)
+fn transliterate(c: char) -> Option<&'static str> {
+    let replacement = match c {
+        'Æ' | 'Ä' => "Ae",
+        'æ' | 'ä' => "ae",
+        'À'..='Å' => "A",
+        'à'..='å' => "a",
+        'Ç' => "C",
+        'ç' => "c",
+        'È'..='Ë' => "E",
+        'è'..='ë' => "e",
+        'Ì'..='Ï' => "I",
+        'ì'..='ï' => "i",
+        'Ð' => "D",
+        'ð' => "d",
+        'Ñ' => "N",
+        'ñ' => "n",
+        'Ö' | 'Ø' => "Oe",
+        'ö' | 'ø' => "oe",
+        'Ò'..='Õ' => "O",
+        'ò'..='õ' => "o",
+        'Ü' => "Ue",
+        'ü' => "ue",
+        'Ù'..='Û' => "U",
+        'ù'..='û' => "u",
+        'Ý' => "Y",
+        'ý' | 'ÿ' => "y",
+        'Þ' => "Th",
+        'þ' => "th",
+        'ß' => "ss",
+        _ => return None,
+    };
+    Some(replacement)
+}
+
+/// Converts a type name to snake case. (ex: `MainMenu` to `main_menu`)
+fn snake_case(name: &str) -> String {
+    let mut output = String::new();
+    let mut previous = None;
+    for c in name.chars() {
+        if c.is_ascii_uppercase()
+            && previous.is_some_and(|p: char| p.is_ascii_alphanumeric() && !p.is_ascii_uppercase())
+        {
+            output.push('_');
+        }
+        output.push(c.to_ascii_lowercase());
+        previous = Some(c);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rust_module() {
+        let mut symbols = Symbols::default();
+        let icon = ResType::GROUP_ICON;
+        symbols.resource(&icon, &ResId::from("app_icon")).unwrap();
+        symbols.resource(&icon, &ResId::from("app_icon")).unwrap();
+        symbols.resource(&icon, &ResId::from(32512)).unwrap();
+        symbols
+            .resource(&ResType::DIALOG, &ResId::from(32512))
+            .unwrap();
+        assert!(symbols.resource(&icon, &ResId::from("app-icon")).is_err());
+
+        let menu = ResId::from("KEYWORD");
+        symbols
+            .commands(&menu, "Menu", "Command", [("Self", 100)], true)
+            .unwrap();
+        symbols
+            .commands(&menu, "Menu", "Command", [("Self", 100)], true)
+            .unwrap();
+        assert!(
+            symbols
+                .commands(&menu, "Menu", "Command", [("Self", 1)], true)
+                .is_err()
+        );
+        symbols
+            .commands(
+                &ResId::from("MAIN_MENU"),
+                "Menu",
+                "Command",
+                [
+                    ("&Open...\tCtrl+O", 101),
+                    ("E&xit", 102),
+                    ("Open", 103),
+                    ("", 104),
+                ],
+                true,
+            )
+            .unwrap();
+
+        symbols.icon_index("text file", 2, 1).unwrap();
+
+        let output = symbols.to_rust();
+        assert!(output.contains(
+            "pub const APP_ICON: *const u16 = {\n    const NAME: &[u16] = &[97, 112, 112,"
+        ));
+        assert!(output.contains("pub enum MainMenuCommand {"));
+        assert!(output.contains("pub const ICON_32512: u16 = 32512;\n"));
+        assert!(output.contains("pub const DIALOG_32512: u16 = 32512;\n"));
+        assert!(output.contains("pub enum KeywordCommand {\n    ItemSelf = 100,\n}"));
+        assert!(output.contains(
+            "    Open = 101,\n    Exit = 102,\n    OpenId103 = 103,\n    Id104 = 104,\n"
+        ));
+        assert!(output.contains("            102 => Ok(Self::Exit),"));
+        assert!(output.contains("pub const ICON_TEXT_FILE: u16 = 2;\n"));
+        assert!(output.contains("pub const ICON_INDEX_TEXT_FILE: u32 = 1;\n"));
+
+        let header = symbols.to_c_header();
+        assert!(header.contains("#ifndef RC_INVOKED\n#define APP_ICON L\"app_icon\"\n#endif\n"));
+        assert!(header.contains("#define ICON_32512 32512\n#define DIALOG_32512 32512\n"));
+        assert!(header.contains("#define MAIN_MENU_COMMAND_OPEN_ID103 103\n"));
+        assert!(header.contains("#define ICON_INDEX_TEXT_FILE 1\n"));
+    }
+
+    #[test]
+    fn translated_commands() {
+        let mut symbols = Symbols::default();
+        let dialog = ResId::from("about");
+        let german = [("Ö&ffnen", 1), ("Schließen", 2), ("Открыть", 3)];
+        symbols
+            .commands(&dialog, "Dialog", "Control", german, false)
+            .unwrap();
+        assert_eq!(
+            symbols.groups[0].commands,
+            [
+                ("Oeffnen".to_string(), 1),
+                ("Schliessen".to_string(), 2),
+                ("Id3".to_string(), 3)
+            ]
+        );
+
+        // The default language names the variants, whatever the order
+        let english = [("Open", 1), ("Close", 2), ("Browse", 3)];
+        symbols
+            .commands(&dialog, "Dialog", "Control", english, true)
+            .unwrap();
+        symbols
+            .commands(&dialog, "Dialog", "Control", german, false)
+            .unwrap();
+        assert!(
+            symbols
+                .to_rust()
+                .contains("    Open = 1,\n    Close = 2,\n    Browse = 3,\n")
+        );
+
+        let missing = [("Öffnen", 1), ("Schließen", 2)];
+        assert!(
+            symbols
+                .commands(&dialog, "Dialog", "Control", missing, false)
+                .is_err()
+        );
+        // Another resource with the same enum name
+        assert!(
+            symbols
+                .commands(&ResId::from("ABOUT_"), "Dialog", "Control", english, true)
+                .is_err()
+        );
+    }
+}
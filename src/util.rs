@@ -31,6 +31,74 @@ pub(crate) fn out_file<P: AsRef<Path>>(file: P) -> Result<PathBuf, EnvError> {
     Ok(path)
 }
 
+/// Writes `data` to a file in `OUT_DIR` named after `prefix` and the hash of the content.
+///
+/// The same content always maps to the same file, so rebuilds don't accumulate files.
+pub(crate) fn hashed_out_file(prefix: &str, ext: &str, data: &[u8]) -> Result<PathBuf, ResError> {
+    // FNV-1a, stable across toolchains and machines
+    let hash = data.iter().fold(0xCBF2_9CE4_8422_2325u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01B3)
+    });
+
+    let path = out_file(format!("{prefix}-{hash:016x}.{ext}"))?;
+    to_file(&path, data)?;
+    Ok(path)
+}
+
+/// SHA-1 digest of the data.
+#[cfg(feature = "etw")]
+pub(crate) fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend((data.len() as u64 * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            w[i] = u32::from_be_bytes(word.try_into().unwrap());
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..20 => ((b & c) | (!b & d), 0x5A827999),
+                20..40 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..60 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0; 20];
+    for (bytes, value) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
 /// Gets the environment varibale.
 pub(crate) fn env_var(var: &'static str) -> Result<String, EnvError> {
     std::env::var(var).map_err(|_| EnvError(var))
@@ -46,6 +114,99 @@ pub(crate) fn to_file<P: AsRef<Path>>(path: P, data: &[u8]) -> std::io::Result<(
     File::create(path)?.write_all(data)
 }
 
+/// Prints a warning displayed by cargo after the build script finishes.
+pub(crate) fn warning<T: std::fmt::Display>(message: T) {
+    println!("cargo:warning={message}");
+}
+
+/// Resource type keywords which can't be used as a name.
+const KEYWORDS: &[&str] = &[
+    "ACCELERATORS",
+    "ANICURSOR",
+    "ANIICON",
+    "BITMAP",
+    "CURSOR",
+    "DIALOG",
+    "DIALOGEX",
+    "FONT",
+    "HTML",
+    "ICON",
+    "LANGUAGE",
+    "MENU",
+    "MENUEX",
+    "MESSAGETABLE",
+    "PLUGPLAY",
+    "RCDATA",
+    "STRINGTABLE",
+    "VERSIONINFO",
+    "VXD",
+];
+
+/// Checks if the name is usable as a resource identifier in the resource file.
+///
+/// It must start with a letter or underscore followed by letters, digits or underscores
+/// and must not be a resource type keyword.
+pub(crate) fn validate_name(name: &str) -> Result<(), ResError> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(name));
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ResError::InvalidIdentifier(name.to_string()))
+    }
+}
+
+/// Appends a little-endian `WORD`.
+pub(crate) fn push_u16(data: &mut Vec<u8>, value: u16) {
+    data.extend(value.to_le_bytes());
+}
+
+/// Appends a little-endian `DWORD`.
+pub(crate) fn push_u32(data: &mut Vec<u8>, value: u32) {
+    data.extend(value.to_le_bytes());
+}
+
+/// Reads a little-endian `WORD`, `None` if out of bounds.
+pub(crate) fn read_u16(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        data.get(offset..offset.checked_add(2)?)?.try_into().ok()?,
+    ))
+}
+
+/// Reads a little-endian `DWORD`, `None` if out of bounds.
+pub(crate) fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        data.get(offset..offset.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+/// Reads a big-endian 16-bit integer, `None` if out of bounds.
+pub(crate) fn read_u16_be(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(
+        data.get(offset..offset.checked_add(2)?)?.try_into().ok()?,
+    ))
+}
+
+/// Reads a big-endian 32-bit integer, `None` if out of bounds.
+pub(crate) fn read_u32_be(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(
+        data.get(offset..offset.checked_add(4)?)?.try_into().ok()?,
+    ))
+}
+
+/// Appends a null-terminated UTF-16 string.
+pub(crate) fn push_utf16(data: &mut Vec<u8>, string: &str) {
+    for unit in string.encode_utf16() {
+        push_u16(data, unit);
+    }
+    push_u16(data, 0);
+}
+
 // Copied from tauri-winres
 /// Escape string for use in the resource file.
 pub(crate) fn escape(string: &str) -> String {
@@ -72,3 +233,30 @@ pub(crate) fn escape_path<P: AsRef<Path>>(path: P) -> Result<String, ResError> {
         ResError::Custom("Failed to convert path to string! Not a valid UTF-8?"),
     )?))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn names() {
+        assert!(validate_name("PNG").is_ok());
+        assert!(validate_name("_app_icon2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("2D").is_err());
+        assert!(validate_name("MY ICON").is_err());
+        assert!(validate_name("\"quoted\"").is_err());
+        assert!(validate_name("rcdata").is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "etw")]
+    fn digest() {
+        let hex = |digest: [u8; 20]| digest.map(|b| format!("{b:02x}")).concat();
+        assert_eq!(hex(sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(
+            hex(sha1(&[b'a'; 1000])),
+            "291e9a6c66994949b57ba5e650361e98fc36b1ba"
+        );
+    }
+}
@@ -1,11 +1,24 @@
-use crate::util;
+use crate::custom::ResType;
+use crate::symbols::Symbols;
+use crate::{ResError, ResId, util};
 
 const MAX_LEVEL: usize = 8;
 
+/// Language used by the resource compiler when none is specified. (`en-US`)
+pub const DEFAULT_LANGUAGE: u16 = 0x0409;
+
 pub struct ResWriter {
     level: usize,
     padding: Vec<u8>,
     buffer: Vec<u8>,
+    /// Language of resources which don't specify one.
+    default_language: u16,
+    /// Language set by the last `LANGUAGE` statement, `None` before the first one.
+    current_language: Option<u16>,
+    /// Normalized type, normalized id and language of the defined resources.
+    defined: Vec<(ResType, ResId, u16)>,
+    /// Symbols of the resource ids and command ids.
+    symbols: Symbols,
 }
 
 impl ResWriter {
@@ -17,7 +30,129 @@ impl ResWriter {
             level: 0,
             padding: b"\t".repeat(MAX_LEVEL),
             buffer,
+            default_language: DEFAULT_LANGUAGE,
+            current_language: None,
+            defined: Vec::new(),
+            symbols: Symbols::default(),
+        }
+    }
+
+    /// Sets the language of resources which don't specify one.
+    pub fn set_default_language(&mut self, language: u16) {
+        self.default_language = language;
+    }
+
+    /// Language of resources which don't specify one.
+    pub fn default_language(&self) -> u16 {
+        self.default_language
+    }
+
+    /// Validates and registers a resource definition and switches to its language.
+    ///
+    /// Returns an error if the id is not valid or a resource with the same type, id and
+    /// language was already defined.
+    pub fn define<T, N>(&mut self, kind: T, id: N, language: Option<u16>) -> Result<(), ResError>
+    where
+        T: Into<ResType>,
+        N: Into<ResId>,
+    {
+        let id = id.into();
+        id.validate()?;
+        self.register(kind, id, language)
+    }
+
+    /// Registers a resource definition without validating its id and switches to its language.
+    ///
+    /// Returns an error if a resource with the same type, id and language was already defined.
+    pub fn register<T, N>(&mut self, kind: T, id: N, language: Option<u16>) -> Result<(), ResError>
+    where
+        T: Into<ResType>,
+        N: Into<ResId>,
+    {
+        let kind = kind.into();
+        let id = id.into();
+        let language = language.unwrap_or(self.default_language);
+
+        // Names are case-insensitive
+        let normalized_kind = kind.normalized();
+        let normalized = id.normalized();
+        let duplicate = self
+            .defined
+            .iter()
+            .any(|(k, i, l)| *k == normalized_kind && *i == normalized && *l == language);
+        if duplicate {
+            return Err(ResError::Duplicate(format!(
+                "Resource \"{id}\" of type {kind} with language {language:#06X}"
+            )));
         }
+        self.symbols.resource(&kind, &id)?;
+        self.defined.push((normalized_kind, normalized, language));
+
+        if self.current_language != Some(language) {
+            self.language(language);
+        }
+        Ok(())
+    }
+
+    /// Adds an enum of command ids to the symbols.
+    ///
+    /// * `owner`: Id of the resource the commands belong to.
+    /// * `kind`: Kind of the owner, used for numeric ids. (ex: `Menu`)
+    /// * `suffix`: Suffix of the enum name. (ex: `Command`)
+    /// * `commands`: Text and id of each command.
+    /// * `language`: Language of the owner, the default language names the variants.
+    pub fn commands<'a, I>(
+        &mut self,
+        owner: &ResId,
+        kind: &str,
+        suffix: &str,
+        commands: I,
+        language: Option<u16>,
+    ) -> Result<(), ResError>
+    where
+        I: IntoIterator<Item = (&'a str, u16)>,
+    {
+        let preferred = language.is_none_or(|language| language == self.default_language);
+        self.symbols
+            .commands(owner, kind, suffix, commands, preferred)
+    }
+
+    /// Adds constants of the id and the index in the executable of an icon to the symbols.
+    ///
+    /// * `name`: Name of the icon, used for the constant names.
+    /// * `id`: Number of the icon group.
+    /// * `index`: Position of the icon group in the resource table.
+    pub fn icon_index(&mut self, name: &str, id: u16, index: usize) -> Result<(), ResError> {
+        self.symbols.icon_index(name, id, index)
+    }
+
+    /// Prints a warning if named icon groups shift the indexes of the icon set, as they are
+    /// ordered before the numbered ones in the resource table.
+    pub(crate) fn check_icon_indexes(&self) {
+        if !self.symbols.has_icon_indexes() {
+            return;
+        }
+
+        let mut named: Vec<&str> = Vec::new();
+        for (kind, id, _) in &self.defined {
+            if *kind == ResType::GROUP_ICON
+                && let ResId::Name(name) = id
+                && !named.contains(&name.as_str())
+            {
+                named.push(name);
+            }
+        }
+        if !named.is_empty() {
+            let names: Vec<String> = named.iter().map(|name| format!("\"{name}\"")).collect();
+            util::warning(format!(
+                "Icons {} are ordered before the icon set, the icon indexes are off",
+                names.join(", ")
+            ));
+        }
+    }
+
+    pub(crate) fn symbols(&self) -> &Symbols {
+        &self.symbols
     }
 
     pub fn as_bytes(&self) -> &[u8] {
@@ -72,6 +207,16 @@ impl ResWriter {
         self.new_line();
     }
 
+    /// Sets the language of the following resources.
+    pub fn language(&mut self, language: u16) {
+        self.current_language = Some(language);
+        self.line(format!(
+            "LANGUAGE {:#04X}, {:#04X}",
+            language & 0x3FF,
+            language >> 10
+        ));
+    }
+
     fn change_level(&mut self, delta: isize) {
         self.level = self.level.saturating_add_signed(delta).min(MAX_LEVEL);
     }
@@ -86,3 +231,30 @@ impl Default for ResWriter {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_variants() {
+        let mut writer = ResWriter::new();
+        writer.define(ResType::ICON, "app", None).unwrap();
+        writer.define(ResType::ICON, "APP", Some(0x0407)).unwrap();
+        writer.define(ResType::BITMAP, "App", Some(0x0407)).unwrap();
+        writer.define(ResType::ICON, 1, None).unwrap();
+
+        assert!(writer.define(ResType::ICON, "my icon", None).is_err());
+
+        assert!(writer.define(ResType::ICON, "App", Some(0x0407)).is_err());
+        assert!(writer.define(ResType::ICON, "001", None).is_err());
+
+        writer.define("png", 9, None).unwrap();
+        assert!(writer.define("PNG", 9, None).is_err());
+
+        let output = String::from_utf8_lossy(writer.as_bytes());
+        // The default language is set explicitly too
+        assert!(output.starts_with("#pragma code_page(65001)\nLANGUAGE 0x09, 0x01\n"));
+        assert_eq!(output.matches("LANGUAGE").count(), 3);
+    }
+}